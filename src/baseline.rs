@@ -0,0 +1,136 @@
+// Baselines of the discrepancies found by the scans, to compare the runs.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use crate::scan::Format;
+
+/// Value for which `Display::fmt` differs from one of the references of [crate::scan::find_issues].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Discrepancy {
+    /// Name of the floating-point type
+    pub float: String,
+    /// Decimal literal of the value
+    pub input: String,
+    /// Number of digits in the fractional part
+    pub precision: usize,
+    /// `format!("{:.precision$}")` of the value
+    pub display: String
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} {}", self.float, self.input, self.precision, self.display)
+    }
+}
+
+/// Parses a discrepancy written by [Discrepancy::fmt]: `float input precision display`.
+impl FromStr for Discrepancy {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid baseline entry '{line}'");
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [float, input, precision, display] => Ok(Discrepancy {
+                float: float.to_string(),
+                input: input.to_string(),
+                precision: usize::from_str(precision).map_err(|_| invalid())?,
+                display: display.to_string(),
+            }),
+            _ => Err(invalid())
+        }
+    }
+}
+
+/// Set of discrepancies found by one or several scans, in the order of the scans.
+///
+/// The baseline is stored as a text file, with one discrepancy per line:
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::baseline::Baseline;
+///
+/// let old = Baseline::from_str("f64 0.15 1 0.1\nf64 0.35 1 0.3\n").unwrap();
+/// let new = Baseline::from_str("f64 0.15 1 0.1\nf64 0.35 1 0.4\n").unwrap();
+/// let (appeared, disappeared) = new.diff(&old);
+/// assert_eq!(appeared.iter().map(|d| d.to_string()).collect::<Vec<_>>(), ["f64 0.35 1 0.4"]);
+/// assert_eq!(disappeared.iter().map(|d| d.to_string()).collect::<Vec<_>>(), ["f64 0.35 1 0.3"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    /// Discrepancies, in the order in which they were found
+    pub entries: Vec<Discrepancy>
+}
+
+impl Baseline {
+    /// Writes the baseline to `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Reads a baseline from `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Baseline> {
+        let text = fs::read_to_string(path)?;
+        Baseline::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Compares the baseline with an `older` one, and returns the discrepancies which appeared,
+    /// then those which disappeared, each of them in the order of their baseline. A discrepancy
+    /// whose Display output changed is in both.
+    pub fn diff<'a>(&'a self, older: &'a Baseline) -> (Vec<&'a Discrepancy>, Vec<&'a Discrepancy>) {
+        let new = self.entries.iter().collect::<HashSet<_>>();
+        let old = older.entries.iter().collect::<HashSet<_>>();
+        let appeared = self.entries.iter().filter(|d| !old.contains(d)).collect();
+        let disappeared = older.entries.iter().filter(|d| !new.contains(d)).collect();
+        (appeared, disappeared)
+    }
+}
+
+/// Parses the content of a baseline file (see [Baseline]).
+impl FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let entries = text.lines().map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(Discrepancy::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Baseline { entries })
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# rounding discrepancies: float input precision display")?;
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the differences between two baselines (see [Baseline::diff]) in `format`: the entries
+/// which `appeared` and `disappeared`, then a summary in [Format::Text]. The CSV records use the
+/// columns of [Format::header].
+pub fn write_diff<W: io::Write>(out: &mut W, format: Format, appeared: &[&Discrepancy], disappeared: &[&Discrepancy]) -> io::Result<()> {
+    let changes = appeared.iter().map(|d| ("appeared", d)).chain(disappeared.iter().map(|d| ("disappeared", d)));
+    for (record, d) in changes {
+        match format {
+            Format::Text => {
+                let sign = if record == "appeared" { '+' } else { '-' };
+                writeln!(out, "{sign} {} {:<8}:{}: {}", d.float, d.input, d.precision, d.display)?
+            }
+            Format::Json => writeln!(out,
+                "{{\"record\":\"{record}\",\"float\":\"{}\",\"input\":\"{}\",\"precision\":{},\"display\":\"{}\"}}",
+                d.float, d.input, d.precision, d.display)?,
+            Format::Csv => writeln!(out, "{record},{},{},{},{},,,,,,,,", d.float, d.input, d.precision, d.display)?,
+        }
+    }
+    if format == Format::Text {
+        writeln!(out, "\n=> {} new and {} disappeared discrepancies since the baseline", appeared.len(), disappeared.len())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+// Checkpoints of long scans, to resume them after an interruption.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::ops::RangeInclusive;
+use crate::{parse_scales, IntegerParts, Policy, TiePattern};
+use crate::iter::MAX_DEPTH;
+use crate::scan::{Generator, Notation, Sample, Summary};
+
+/// State of a scan by [crate::scan::find_issues]: its parameters, the index of the next value
+/// to test, and the counters so far.
+///
+/// The checkpoint is stored as a text file, with one `key=value` per line:
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::Policy;
+/// use rounding::checkpoint::Checkpoint;
+///
+/// let checkpoint = Checkpoint::from_str(
+///     "float=f64\ndepth=12\nnegative=false\npolicy=even\nnext=1000\ntests=1000\nerrors=499\nbugs=0\nintents=499\n"
+/// ).unwrap();
+/// assert_eq!(checkpoint.policy, Policy::ToEven);
+/// assert_eq!(checkpoint.next, 1000);
+/// assert_eq!(checkpoint.summary.errors, 499);
+/// assert!(Checkpoint::from_str("float=f64\ndepth=19\nnegative=false\npolicy=even\nnext=0\n").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Name of the floating-point type
+    pub float: String,
+    /// Maximum number of fractional digits
+    pub depth: usize,
+    /// Tests negative values instead of positive ones
+    pub negative: bool,
+    /// Generator of the values (ties if the key is missing)
+    pub generator: Generator,
+    /// Integer parts of the values (0 if the key is missing)
+    pub integers: IntegerParts,
+    /// Powers of 10 by which the values are scaled (0 if the key is missing)
+    pub scales: RangeInclusive<i32>,
+    /// Endings of the values (5 if the key is missing)
+    pub pattern: TiePattern,
+    /// Notation of the rounded values (fixed if the key is missing)
+    pub notation: Notation,
+    /// Rounding policy of the string-based rounding
+    pub policy: Policy,
+    /// Random sample of the values, if any
+    pub sample: Option<Sample>,
+    /// Index of the next value to test in the generator, or of the next sample
+    pub next: u64,
+    /// Counters of the values tested so far
+    pub summary: Summary
+}
+
+impl Checkpoint {
+    /// Writes the checkpoint to `path`. The file is replaced atomically, so that a valid
+    /// checkpoint remains if the program is interrupted while writing it.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.to_string())?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Reads a checkpoint from `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Checkpoint> {
+        let text = fs::read_to_string(path)?;
+        Checkpoint::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Parses the content of a checkpoint file (see [Checkpoint]).
+impl FromStr for Checkpoint {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut float = None;
+        let mut depth = None;
+        let mut negative = None;
+        let mut generator = Generator::default();
+        let mut integers = IntegerParts::default();
+        let mut scales = 0..=0;
+        let mut pattern = TiePattern::default();
+        let mut notation = Notation::default();
+        let mut policy = None;
+        let mut next = None;
+        let (mut count, mut seed) = (None, None);
+        let mut summary = Summary::default();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (key, value) = line.split_once('=').ok_or_else(|| format!("invalid checkpoint line '{line}'"))?;
+            match key {
+                "float" => match value {
+                    "f32" | "f64" => float = Some(value.to_string()),
+                    _ => return Err(format!("invalid checkpoint value '{line}'"))
+                },
+                "depth" => match parse_value(line, value)? {
+                    d @ 1..=MAX_DEPTH => depth = Some(d),
+                    _ => return Err(format!("invalid checkpoint value '{line}'"))
+                },
+                "negative" => negative = Some(parse_value(line, value)?),
+                "generator" => generator = Generator::from_str(value)?,
+                "integers" => integers = IntegerParts::from_str(value)?,
+                "scales" => scales = parse_scales(value)?,
+                "pattern" => pattern = TiePattern::from_str(value)?,
+                "notation" => notation = Notation::from_str(value)?,
+                "policy" => policy = Some(parse_value(line, value)?),
+                "sample" => count = Some(parse_value(line, value)?),
+                "seed" => seed = Some(parse_value(line, value)?),
+                "next" => next = Some(parse_value(line, value)?),
+                "tests" => summary.tests = parse_value(line, value)?,
+                "errors" => summary.errors = parse_value(line, value)?,
+                "bugs" => summary.bugs = parse_value(line, value)?,
+                "intents" => summary.intents = parse_value(line, value)?,
+                "draws" => summary.draws = parse_value(line, value)?,
+                "failed_draws" => summary.failed_draws = parse_value(line, value)?,
+                _ => return Err(format!("unknown checkpoint key '{key}'"))
+            }
+        }
+        let missing = |key| format!("missing checkpoint key '{key}'");
+        Ok(Checkpoint {
+            float: float.ok_or_else(|| missing("float"))?,
+            depth: depth.ok_or_else(|| missing("depth"))?,
+            negative: negative.ok_or_else(|| missing("negative"))?,
+            generator,
+            integers,
+            scales,
+            pattern,
+            notation,
+            policy: policy.ok_or_else(|| missing("policy"))?,
+            sample: match (count, seed) {
+                (Some(count), Some(seed)) => Some(Sample { count, seed }),
+                (None, None) => None,
+                _ => return Err("incomplete checkpoint sample".to_string())
+            },
+            next: next.ok_or_else(|| missing("next"))?,
+            summary
+        })
+    }
+}
+
+/// Parses the `value` of a checkpoint `line`.
+fn parse_value<T: FromStr>(line: &str, value: &str) -> Result<T, String> {
+    T::from_str(value).map_err(|_| format!("invalid checkpoint value '{line}'"))
+}
+
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# rounding scan checkpoint")?;
+        writeln!(f, "float={}", self.float)?;
+        writeln!(f, "depth={}", self.depth)?;
+        writeln!(f, "negative={}", self.negative)?;
+        writeln!(f, "generator={}", self.generator.name())?;
+        writeln!(f, "integers={}", self.integers)?;
+        writeln!(f, "scales={}..={}", self.scales.start(), self.scales.end())?;
+        writeln!(f, "pattern={}", self.pattern)?;
+        writeln!(f, "notation={}", self.notation.name())?;
+        writeln!(f, "policy={}", self.policy.name())?;
+        if let Some(sample) = self.sample {
+            writeln!(f, "sample={}", sample.count)?;
+            writeln!(f, "seed={}", sample.seed)?;
+        }
+        writeln!(f, "next={}", self.next)?;
+        writeln!(f, "tests={}", self.summary.tests)?;
+        writeln!(f, "errors={}", self.summary.errors)?;
+        writeln!(f, "bugs={}", self.summary.bugs)?;
+        writeln!(f, "intents={}", self.summary.intents)?;
+        if self.sample.is_some() {
+            writeln!(f, "draws={}", self.summary.draws)?;
+            writeln!(f, "failed_draws={}", self.summary.failed_draws)?;
+        }
+        Ok(())
+    }
+}
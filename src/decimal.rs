@@ -0,0 +1,534 @@
+// Arbitrary-precision decimal values, used as an exact reference for the rounding.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter, LowerExp};
+use std::ops::{Neg, Sub};
+use std::str::FromStr;
+use crate::{Float, Policy};
+
+//==============================================================================
+// Decimal type
+//------------------------------------------------------------------------------
+
+/// Arbitrary-precision decimal value, `(-1)^negative * digits * 10^exp`.
+///
+/// The representation is normalized: `digits` has neither leading nor trailing zeros, and
+/// the value zero has no digit and a null exponent. The sign of zero is kept, so that `-0.04`
+/// can be rounded to `-0`, as `Display::fmt` does for `f64`.
+///
+/// ```
+/// use rounding::{BigDecimal, Policy};
+///
+/// let d = "-12.3400".parse::<BigDecimal>().unwrap();
+/// assert_eq!(d.to_string(), "-12.34");
+/// assert_eq!(format!("{:.1}", d.round(1, &Policy::AwayFromZero)), "-12.3");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BigDecimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exp: i32
+}
+
+/// Number of decimal digits in a limb of [BigDecimal::from_binary].
+const LIMB_DIGITS: usize = 9;
+/// Value of a limb of [BigDecimal::from_binary] (10^LIMB_DIGITS).
+const LIMB_BASE: u64 = 1_000_000_000;
+
+impl BigDecimal {
+    /// Creates a decimal value equal to `(-1)^negative * digits * 10^exp`.
+    ///
+    /// * `negative`: sign of the value
+    /// * `digits`: decimal digits 0-9 of the significand, most significant first
+    /// * `exp`: power of 10 of the last digit
+    pub fn new(negative: bool, digits: Vec<u8>, exp: i32) -> BigDecimal {
+        debug_assert!(digits.iter().all(|&d| d < 10), "invalid digits: {digits:?}");
+        let mut value = BigDecimal { negative, digits, exp };
+        value.normalize();
+        value
+    }
+
+    /// Exact value of `m * 2^e`, with the given sign. Every binary value has a finite decimal
+    /// representation, since 2^-k = 5^k / 10^k.
+    pub fn from_binary(negative: bool, m: u64, e: i32) -> BigDecimal {
+        // little-endian limbs of 'm * 2^e' if e >= 0, or of 'm * 5^-e' otherwise:
+        let mut limbs = vec![m % LIMB_BASE, m / LIMB_BASE % LIMB_BASE, m / LIMB_BASE / LIMB_BASE];
+        let (factor, mut count) = if e >= 0 { (2_u64, e) } else { (5, -e) };
+        while count > 0 {
+            // multiplies by up to 2^32 or 5^13 at a time, so that limb * mul + carry fits in an u64
+            let step = count.min(if factor == 2 { 32 } else { 13 });
+            let mul = factor.pow(step as u32);
+            let mut carry = 0;
+            for limb in limbs.iter_mut() {
+                let x = *limb * mul + carry;
+                *limb = x % LIMB_BASE;
+                carry = x / LIMB_BASE;
+            }
+            while carry > 0 {
+                limbs.push(carry % LIMB_BASE);
+                carry /= LIMB_BASE;
+            }
+            count -= step;
+        }
+        let mut digits = Vec::with_capacity(limbs.len() * LIMB_DIGITS);
+        for mut limb in limbs.into_iter().rev() {
+            let start = digits.len();
+            for _ in 0..LIMB_DIGITS {
+                digits.push((limb % 10) as u8);
+                limb /= 10;
+            }
+            digits[start..].reverse();
+        }
+        BigDecimal::new(negative, digits, e.min(0))
+    }
+
+    /// Exact value of `n`, or `None` if `n` is infinite or NaN.
+    ///
+    /// ```
+    /// use rounding::BigDecimal;
+    ///
+    /// let d = BigDecimal::from_float(-2.5e-3_f32).unwrap();
+    /// assert_eq!(d.to_string(), "-0.0024999999441206455230712890625");
+    /// ```
+    pub fn from_float<T: Float>(n: T) -> Option<BigDecimal> {
+        if !n.is_finite() {
+            return None;
+        }
+        let (negative, m, e) = n.decompose();
+        Some(BigDecimal::from_binary(negative, m, e))
+    }
+
+    /// Exact value of `n`, or `None` if `n` is infinite or NaN.
+    ///
+    /// ```
+    /// use rounding::BigDecimal;
+    ///
+    /// let d = BigDecimal::from_f64(0.1).unwrap();
+    /// assert_eq!(d.to_string(), "0.1000000000000000055511151231257827021181583404541015625");
+    /// ```
+    pub fn from_f64(n: f64) -> Option<BigDecimal> {
+        BigDecimal::from_float(n)
+    }
+
+    /// Exact value of `n`, or `None` if `n` is infinite or NaN.
+    ///
+    /// ```
+    /// use rounding::BigDecimal;
+    ///
+    /// let d = BigDecimal::from_f32(0.1).unwrap();
+    /// assert_eq!(d.to_string(), "0.100000001490116119384765625");
+    /// ```
+    pub fn from_f32(n: f32) -> Option<BigDecimal> {
+        BigDecimal::from_float(n)
+    }
+
+    /// Sign of the value (zero may be negative).
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Decimal digits 0-9 of the significand, most significant first, without leading or
+    /// trailing zeros.
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    /// Power of 10 of the last digit in [BigDecimal::digits].
+    pub fn exponent(&self) -> i32 {
+        self.exp
+    }
+
+    /// Rounds the value to `pr` digits in the fractional part, using the rounding `policy`.
+    ///
+    /// * `pr`: number of digits to keep in the fractional part
+    /// * `policy`: rounding policy
+    ///
+    /// ```
+    /// use rounding::{BigDecimal, Policy};
+    ///
+    /// let d = "2.25".parse::<BigDecimal>().unwrap();
+    /// assert_eq!(d.round(1, &Policy::ToEven).to_string(), "2.2");
+    /// assert_eq!(d.round(1, &Policy::AwayFromZero).to_string(), "2.3");
+    /// ```
+    pub fn round(&self, pr: usize, policy: &Policy) -> BigDecimal {
+        self.round_to_exp(-i64::try_from(pr).unwrap_or(i64::MAX), policy)
+    }
+
+    /// Rounds the value at a signed digit `position`, using the rounding `policy`: a positive
+    /// position is a number of digits in the fractional part, like [BigDecimal::round], and a
+    /// negative one rounds the integer part to a multiple of `10^-position`.
+    ///
+    /// * `position`: number of fractional digits to keep, or opposite of the number of integer
+    ///   digits to clear
+    /// * `policy`: rounding policy
+    ///
+    /// ```
+    /// use rounding::{BigDecimal, Policy};
+    ///
+    /// let d = "12345.6".parse::<BigDecimal>().unwrap();
+    /// assert_eq!(d.round_at(-2, &Policy::ToEven).to_string(), "12300");
+    /// let d = "99950".parse::<BigDecimal>().unwrap();
+    /// assert_eq!(d.round_at(-2, &Policy::ToEven).to_string(), "100000");
+    /// ```
+    pub fn round_at(&self, position: i32, policy: &Policy) -> BigDecimal {
+        self.round_to_exp(-(position as i64), policy)
+    }
+
+    /// Rounds the value to `digits` significant digits, using the rounding `policy`. The result
+    /// may have one more significant digit, followed by zeros, when a carry propagates through
+    /// all the digits, like 9.99 rounded to 2 digits.
+    ///
+    /// * `digits`: number of significant digits to keep, at least 1
+    /// * `policy`: rounding policy
+    ///
+    /// ```
+    /// use rounding::{BigDecimal, Policy};
+    ///
+    /// let d = "0.00012345".parse::<BigDecimal>().unwrap();
+    /// assert_eq!(d.round_significant(3, &Policy::ToEven).to_string(), "0.000123");
+    /// let d = "-123456".parse::<BigDecimal>().unwrap();
+    /// assert_eq!(d.round_significant(3, &Policy::Floor).to_string(), "-124000");
+    /// ```
+    pub fn round_significant(&self, digits: usize, policy: &Policy) -> BigDecimal {
+        assert!(digits > 0, "the number of significant digits must be at least 1");
+        match self.leading_exponent() {
+            Some(lead) => self.round_to_exp(lead as i64 + 1 - i64::try_from(digits).unwrap_or(i64::MAX), policy),
+            None => self.clone()
+        }
+    }
+
+    /// Power of 10 of the leading digit, or `None` if the value is 0.
+    ///
+    /// ```
+    /// use rounding::BigDecimal;
+    ///
+    /// assert_eq!("0.0012".parse::<BigDecimal>().unwrap().leading_exponent(), Some(-3));
+    /// assert_eq!("-120".parse::<BigDecimal>().unwrap().leading_exponent(), Some(2));
+    /// assert_eq!("0".parse::<BigDecimal>().unwrap().leading_exponent(), None);
+    /// ```
+    pub fn leading_exponent(&self) -> Option<i32> {
+        (!self.is_zero()).then(|| self.exp + self.digits.len() as i32 - 1)
+    }
+
+    /// Rounds the value to a multiple of `10^new_exp`, using the rounding `policy`. Beyond the
+    /// leading digit, the result is 0, or the power of 10 away from zero, which is limited to
+    /// `10^(i32::MAX - 1)`.
+    fn round_to_exp(&self, new_exp: i64, policy: &Policy) -> BigDecimal {
+        // a zero has no digit to drop, whatever the position
+        if self.exp as i64 >= new_exp || self.is_zero() {
+            return self.clone();
+        }
+        // the exponent of the result, and of its leading digit after a carry, must fit in an i32
+        let new_exp = new_exp.min(i32::MAX as i64 - 1) as i32;
+        // number of dropped digits:
+        let drop = usize::try_from(new_exp as i64 - self.exp as i64).unwrap_or(usize::MAX);
+        let keep = self.digits.len().saturating_sub(drop);
+        let mut digits = self.digits[..keep].to_vec();
+        // first dropped digit, which is an implicit zero if all the digits are dropped:
+        let first = if drop > self.digits.len() { 0 } else { self.digits[keep] };
+        let sticky = drop > self.digits.len() || self.digits[keep + 1..].iter().any(|&d| d != 0);
+        let half = match first.cmp(&5) {
+            Ordering::Less => Ordering::Less,
+            Ordering::Equal if !sticky => Ordering::Equal,
+            _ => Ordering::Greater
+        };
+        // last kept digit, which is an implicit zero if all the digits are dropped:
+        let last = digits.last().copied().unwrap_or(0);
+        let odd = last & 1 != 0;
+        // the dropped digits aren't null, so the rounding is inexact; 'up' means away from zero:
+        let up = match policy {
+            Policy::ToEven => half == Ordering::Greater || half == Ordering::Equal && odd,
+            Policy::AwayFromZero => half != Ordering::Less,
+            Policy::TowardZero => false,
+            Policy::Floor => self.negative,
+            Policy::Ceiling => !self.negative,
+            Policy::HalfTowardZero => half == Ordering::Greater,
+            Policy::HalfDown => half == Ordering::Greater || half == Ordering::Equal && self.negative,
+            Policy::HalfUp => half == Ordering::Greater || half == Ordering::Equal && !self.negative,
+            Policy::HalfOdd => half == Ordering::Greater || half == Ordering::Equal && !odd,
+            Policy::ZeroFiveUp => last == 0 || last == 5,
+        };
+        if up {
+            // increments the significand:
+            let mut carry = true;
+            for d in digits.iter_mut().rev() {
+                if *d == 9 {
+                    *d = 0;
+                } else {
+                    *d += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                digits.insert(0, 1);
+            }
+        }
+        BigDecimal::new(self.negative, digits, new_exp)
+    }
+
+    /// Removes the leading and trailing zeros.
+    fn normalize(&mut self) {
+        let leading = self.digits.iter().take_while(|&&d| d == 0).count();
+        self.digits.drain(..leading);
+        let trailing = self.digits.iter().rev().take_while(|&&d| d == 0).count();
+        self.digits.truncate(self.digits.len() - trailing);
+        if self.digits.is_empty() {
+            self.exp = 0;
+        } else {
+            self.exp += trailing as i32;
+        }
+    }
+
+    /// Compares the absolute values.
+    fn cmp_abs(&self, other: &BigDecimal) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => {
+                // power of 10 of the leading digit + 1:
+                let self_mag = self.exp + self.digits.len() as i32;
+                let other_mag = other.exp + other.digits.len() as i32;
+                self_mag.cmp(&other_mag).then_with(|| self.digits.cmp(&other.digits))
+            }
+        }
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Numerical comparison, so `-0` and `0` are equal.
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let self_neg = self.negative && !self.is_zero();
+        let other_neg = other.negative && !other.is_zero();
+        match (self_neg, other_neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_abs(other),
+            (true, true) => other.cmp_abs(self)
+        }
+    }
+}
+
+//==============================================================================
+// Arithmetic
+//------------------------------------------------------------------------------
+
+impl BigDecimal {
+    /// Absolute value.
+    pub fn abs(&self) -> BigDecimal {
+        BigDecimal { negative: false, ..self.clone() }
+    }
+
+    /// Digits of the absolute value, least significant first, from the power of 10 `exp`,
+    /// which must not be greater than `self.exp`.
+    fn aligned_digits(&self, exp: i32, len: usize) -> Vec<u8> {
+        let mut digits = vec![0; len];
+        let shift = (self.exp - exp) as usize;
+        for (i, &d) in self.digits.iter().rev().enumerate() {
+            digits[shift + i] = d;
+        }
+        digits
+    }
+}
+
+/// Exact difference.
+///
+/// ```
+/// use rounding::BigDecimal;
+///
+/// let a = "0.15".parse::<BigDecimal>().unwrap();
+/// let b = "1.2".parse::<BigDecimal>().unwrap();
+/// assert_eq!((&a - &b).to_string(), "-1.05");
+/// assert_eq!((&b - &a).to_string(), "1.05");
+/// assert_eq!((&a - &-&b).to_string(), "1.35");
+/// ```
+impl Sub for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn sub(self, other: &BigDecimal) -> BigDecimal {
+        if other.is_zero() {
+            return self.clone();
+        }
+        if self.is_zero() {
+            return -other;
+        }
+        let exp = self.exp.min(other.exp);
+        let len = 1 + (self.exp + self.digits.len() as i32).max(other.exp + other.digits.len() as i32) - exp;
+        let a = self.aligned_digits(exp, len as usize);
+        let b = other.aligned_digits(exp, len as usize);
+        let (negative, digits) = if self.negative != other.negative {
+            // same sign as self, sum of the absolute values
+            let mut carry = 0;
+            let sum = a.iter().zip(&b).map(|(&x, &y)| {
+                let d = x + y + carry;
+                carry = d / 10;
+                d % 10
+            }).collect::<Vec<_>>();
+            (self.negative, sum)
+        } else {
+            // difference of the absolute values, with the sign of the greater one
+            let (negative, big, small) = match self.cmp_abs(other) {
+                Ordering::Less => (!self.negative, b, a),
+                Ordering::Equal => (false, a, b),
+                Ordering::Greater => (self.negative, a, b)
+            };
+            let mut borrow = 0;
+            let diff = big.iter().zip(&small).map(|(&x, &y)| {
+                let d = x as i8 - y as i8 - borrow;
+                borrow = (d < 0) as i8;
+                (d + 10 * borrow) as u8
+            }).collect::<Vec<_>>();
+            (negative, diff)
+        };
+        BigDecimal::new(negative, digits.into_iter().rev().collect(), exp)
+    }
+}
+
+impl Neg for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        BigDecimal { negative: !self.negative, ..self.clone() }
+    }
+}
+
+//==============================================================================
+// Conversion from and to strings
+//------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDecimalError(String);
+
+impl Display for ParseDecimalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid decimal value '{}'", self.0)
+    }
+}
+
+impl Error for ParseDecimalError {}
+
+/// Parses `[+-]digits[.digits][(e|E)[+-]digits]`, where at least one digit is required in
+/// the significand. The powers of 10 of the digits must be within `-i32::MAX..i32::MAX`.
+///
+/// ```
+/// use rounding::BigDecimal;
+///
+/// assert!("1e2147483646".parse::<BigDecimal>().is_ok());
+/// assert!("10e2147483646".parse::<BigDecimal>().is_err());
+/// assert!("1e-2147483648".parse::<BigDecimal>().is_err());
+/// ```
+impl FromStr for BigDecimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDecimalError(s.to_string());
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s)
+        };
+        let (significand, exp) = match unsigned.find(['e', 'E']) {
+            Some(pos) => (&unsigned[..pos], i32::from_str(&unsigned[pos + 1..]).map_err(|_| error())?),
+            None => (unsigned, 0)
+        };
+        let (int, frac) = significand.split_once('.').unwrap_or((significand, ""));
+        if int.is_empty() && frac.is_empty() {
+            return Err(error());
+        }
+        let mut digits = Vec::with_capacity(int.len() + frac.len());
+        for ch in int.bytes().chain(frac.bytes()) {
+            if !ch.is_ascii_digit() {
+                return Err(error());
+            }
+            digits.push(ch - b'0');
+        }
+        // the exponents of the last and of the leading digits, and their opposites, must fit in an i32
+        let exp = exp as i64 - frac.len() as i64;
+        let out_of_range = exp < -(i32::MAX as i64) || exp + digits.len() as i64 > i32::MAX as i64;
+        if out_of_range && digits.iter().any(|&d| d != 0) {
+            return Err(error());
+        }
+        Ok(BigDecimal::new(negative, digits, exp.max(i32::MIN as i64) as i32))
+    }
+}
+
+/// Writes the value in fixed notation. If a precision is given, the value is rounded half to
+/// even to that number of fractional digits, like `f64`, otherwise it is written exactly.
+impl Display for BigDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (value, pr) = match f.precision() {
+            Some(pr) => (self.round(pr, &Policy::ToEven), pr),
+            None => (self.clone(), (-self.exp).max(0) as usize)
+        };
+        let len = value.digits.len() as i32;
+        // number of digits in the integer part, excluding the leading zeros:
+        let int_len = (len + value.exp).max(0) as usize;
+        let mut s = String::with_capacity(int_len + pr + 2);
+        if int_len == 0 {
+            s.push('0');
+        } else {
+            s.extend(value.digits.iter().take(int_len).map(|&d| (b'0' + d) as char));
+            s.extend(std::iter::repeat_n('0', int_len.saturating_sub(value.digits.len())));
+        }
+        if pr > 0 {
+            s.push('.');
+            // position of the first fractional digit in 'digits', which may be negative:
+            let first = len + value.exp;
+            s.extend((0..pr as i32).map(|i| {
+                let pos = first + i;
+                if pos >= 0 && pos < len { (b'0' + value.digits[pos as usize]) as char } else { '0' }
+            }));
+        }
+        f.pad_integral(!value.negative, "", &s)
+    }
+}
+
+/// Writes the value in scientific notation, like `f64`: `1.5e3`, `-2e-7`. If a precision is
+/// given, the value is rounded half to even to that number of digits after the leading one, and
+/// the exponent is adjusted if the rounding propagates a carry, otherwise it is written exactly.
+///
+/// ```
+/// use rounding::BigDecimal;
+///
+/// let d = "9995".parse::<BigDecimal>().unwrap();
+/// assert_eq!(format!("{d:e}"), "9.995e3");
+/// assert_eq!(format!("{d:.2e}"), "1.00e4");
+/// assert_eq!(format!("{:.3e}", "-0.00012".parse::<BigDecimal>().unwrap()), "-1.200e-4");
+/// ```
+impl LowerExp for BigDecimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match f.precision() {
+            Some(pr) => self.round_significant(pr.saturating_add(1), &Policy::ToEven),
+            None => self.clone()
+        };
+        let pr = f.precision().unwrap_or(value.digits.len().saturating_sub(1));
+        let mut s = String::with_capacity(pr + 8);
+        s.push((b'0' + value.digits.first().copied().unwrap_or(0)) as char);
+        if pr > 0 {
+            s.push('.');
+            s.extend((1..=pr).map(|i| (b'0' + value.digits.get(i).copied().unwrap_or(0)) as char));
+        }
+        s.push_str(&format!("e{}", value.leading_exponent().unwrap_or(0)));
+        f.pad_integral(!value.negative, "", &s)
+    }
+}
@@ -0,0 +1,140 @@
+// Detailed explanation of the rounding of a single value.
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::str::FromStr;
+use crate::{str_sround, BigDecimal, Float, Policy};
+
+/// Explains the rounding of the decimal literal `value` to `pr` fractional digits by `Display::fmt`,
+/// once parsed as a `T`: its bits, its exact decimal value and those of its neighbours, its distance
+/// to the decimal tie at that precision, the result of Display, the rounding of the literal and of
+/// the exact value under each [Policy], and a verdict.
+///
+/// The verdict is the same as [crate::scan::find_issues], which compares Display with the rounding
+/// of the exact value to even, then with the rounding of the literal under `policy`.
+///
+/// * `value`: decimal literal, like "0.15"
+/// * `pr`: number of digits to keep in the fractional part
+/// * `policy`: rounding policy of the literal, for the verdict
+///
+/// ```
+/// use rounding::explain::explain;
+/// use rounding::Policy;
+///
+/// let text = explain::<f64>("0.15", 1, &Policy::ToEven).unwrap();
+/// assert!(text.contains("exact      : 0.1499999999999999944488848768742172978818416595458984375\n"));
+/// assert!(text.contains("verdict    : decimal-intent mismatch"));
+/// assert!(explain::<f64>("1e400", 1, &Policy::ToEven).is_err());
+/// ```
+pub fn explain<T: Float>(value: &str, pr: usize, policy: &Policy) -> Result<String, String> {
+    let literal = BigDecimal::from_str(value).map_err(|e| e.to_string())?;
+    let val = T::from_str(value).map_err(|_| format!("cannot convert '{value}' to {}", T::NAME))?;
+    let exact = BigDecimal::from_float(val).ok_or_else(|| format!("'{value}' isn't a finite {} value", T::NAME))?;
+    let mut out = String::new();
+    // writing to a String can't fail
+    let _ = write_explanation(&mut out, value, &literal, val, &exact, pr, policy);
+    Ok(out)
+}
+
+fn write_explanation<T: Float>(out: &mut String, value: &str, literal: &BigDecimal, val: T, exact: &BigDecimal,
+                               pr: usize, policy: &Policy) -> std::fmt::Result
+{
+    let (negative, m, e) = val.decompose();
+    let sign = if negative { "-" } else { "+" };
+    let width = T::BITS as usize / 4;
+    writeln!(out, "value      : {value} ({})", T::NAME)?;
+    writeln!(out, "bits       : 0x{:0width$x} = {sign}{m:#x} * 2^{e}", val.to_bits_u64())?;
+    writeln!(out, "exact      : {exact}")?;
+    for (name, neighbour) in [("next_down", val.next_down()), ("next_up", val.next_up())] {
+        match BigDecimal::from_float(neighbour) {
+            Some(n) => writeln!(out, "{name:<11}: {n}")?,
+            None => writeln!(out, "{name:<11}: {neighbour}")?,
+        }
+    }
+
+    // tie between the two candidates of the rounding, on the side of the value
+    let magnitude = exact.abs();
+    let tie = &magnitude.round(pr, &Policy::TowardZero) - &-&BigDecimal::new(false, vec![5], -(pr as i32) - 1);
+    let distance = &magnitude - &tie;
+    // gap to the next value away from zero, or toward zero for the greatest finite value
+    let (away, toward) = if negative { (val.next_down(), val.next_up()) } else { (val.next_up(), val.next_down()) };
+    let ulp = match BigDecimal::from_float(away) {
+        Some(away) => &away.abs() - &magnitude,
+        None => &magnitude - &BigDecimal::from_float(toward).unwrap_or_else(|| magnitude.clone()).abs(),
+    };
+    let side = side_of_tie(&magnitude, &tie);
+    writeln!(out, "tie        : {}{tie}", if negative { "-" } else { "" })?;
+    writeln!(out, "distance   : {distance} = {} ulp ({side} in magnitude)", ratio(&distance, &ulp))?;
+
+    let display = format!("{val:.pr$}");
+    let exact_round = format!("{:.pr$}", exact.round(pr, &Policy::ToEven));
+    let literal_round = str_sround(value, pr, policy);
+    writeln!(out, "Display    : {display}")?;
+    writeln!(out)?;
+    writeln!(out, "{:<10} {:<width$} exact", "policy", "literal", width = literal_round.len().max(7))?;
+    for p in Policy::ALL {
+        writeln!(out, "{:<10} {:<width$} {:.pr$}", p.name(), str_sround(value, pr, &p), exact.round(pr, &p),
+                 width = literal_round.len().max(7))?;
+    }
+    writeln!(out)?;
+
+    let verdict = if display != exact_round {
+        format!("Display bug: Display gives {display}, but the exact value {exact} rounds to {exact_round} \
+                 (to nearest, ties to even), since it's {side}")
+    } else if display != literal_round {
+        if literal == exact {
+            format!("decimal-intent mismatch: Display gives {display}, which is the correct rounding of the exact \
+                     value, {side}; the literal {value} is exactly representable in {}, but rounds to \
+                     {literal_round} under the policy {}, instead of to even", T::NAME, policy.name())
+        } else {
+            let literal_side = side_of_tie(&literal.abs(), &tie);
+            format!("decimal-intent mismatch: Display gives {display}, which is the correct rounding of the exact \
+                     value, {side}, but the literal {value} is {literal_side} and rounds to {literal_round} ({}); \
+                     {value} isn't representable in {}", policy.name(), T::NAME)
+        }
+    } else {
+        format!("ok: Display gives {display}, which is the correct rounding of the exact value, {side}, and of \
+                 the literal ({})", policy.name())
+    };
+    writeln!(out, "verdict    : {verdict}")
+}
+
+/// Position of the absolute value `magnitude` relative to the `tie`.
+fn side_of_tie(magnitude: &BigDecimal, tie: &BigDecimal) -> &'static str {
+    match magnitude.cmp(tie) {
+        Ordering::Less => "below the tie",
+        Ordering::Equal => "exactly on the tie",
+        Ordering::Greater => "above the tie",
+    }
+}
+
+/// Approximate ratio `a / b` of two decimal values, which may be too small or too large for an `f64`,
+/// with 4 fractional digits, or with 4 significant digits in scientific notation if it's below 1e-4
+/// or beyond the range of an `f64`.
+fn ratio(a: &BigDecimal, b: &BigDecimal) -> String {
+    if b.is_zero() {
+        return f64::NAN.to_string();
+    }
+    if a.is_zero() {
+        return format!("{:.4}", 0.0);
+    }
+    // x = mx * 10^ex, with 0.1 <= |mx| < 1
+    let significand = |x: &BigDecimal| {
+        let digits = x.digits().iter().take(20).map(|&d| (b'0' + d) as char).collect::<String>();
+        let m = f64::from_str(&format!("0.{digits}")).unwrap_or(0.0);
+        (if x.is_negative() { -m } else { m }, x.exponent() + x.digits().len() as i32)
+    };
+    let (ma, ea) = significand(a);
+    let (mb, eb) = significand(b);
+    // a / b = m * 10^e, with 0.1 < |m| < 10: the exponents are combined before scaling m
+    let (m, e) = (ma / mb, ea - eb);
+    if e.abs() < 300 {
+        let ratio = m * 10_f64.powi(e);
+        if ratio.abs() >= 1e-4 {
+            return format!("{ratio:.4}");
+        }
+    }
+    let text = format!("{m:.3e}");
+    let (digits, k) = text.split_once('e').expect("missing exponent");
+    format!("{digits}e{}", i32::from_str(k).expect("invalid exponent") + e)
+}
@@ -0,0 +1,169 @@
+// Abstraction of the IEEE 754 binary floating-point types.
+
+use std::fmt::{Debug, Display, LowerExp};
+use std::ops::{Div, Mul};
+use std::str::FromStr;
+
+/// What the rounding code needs from an IEEE 754 binary floating-point type.
+///
+/// ```
+/// use rounding::Float;
+///
+/// assert_eq!(1.5_f64.decompose(), (false, 3 << 51, -52));
+/// assert_eq!((-1.5_f32).decompose(), (true, 3 << 22, -23));
+/// assert_eq!(f32::from_bits_u64(1).decompose(), (false, 1, -149));
+/// ```
+pub trait Float: Copy + Debug + Display + LowerExp + FromStr + PartialOrd
+    + Mul<Output = Self> + Div<Output = Self>
+{
+    /// Name of the type, like "f64".
+    const NAME: &'static str;
+    /// Total number of bits.
+    const BITS: u32;
+    /// Number of explicit bits of the mantissa (the leading 1 of normal values is implicit).
+    const MANTISSA_BITS: u32;
+    /// Exponent bias.
+    const EXP_BIAS: i32;
+
+    fn to_bits_u64(self) -> u64;
+    fn from_bits_u64(bits: u64) -> Self;
+    /// Nearest value converted from an `f64`.
+    fn from_f64(n: f64) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_normal(self) -> bool;
+    fn is_sign_negative(self) -> bool;
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    /// Least value greater than `self`.
+    fn next_up(self) -> Self;
+    /// Greatest value less than `self`.
+    fn next_down(self) -> Self;
+
+    /// Decomposes a finite value into `(negative, m, e)`, so that it equals `(-1)^negative * m * 2^e`.
+    fn decompose(self) -> (bool, u64, i32) {
+        let bits = self.to_bits_u64();
+        let exp_mask = (1 << (Self::BITS - Self::MANTISSA_BITS - 1)) - 1;
+        let biased_exp = ((bits >> Self::MANTISSA_BITS) & exp_mask) as i32;
+        let fraction = bits & ((1 << Self::MANTISSA_BITS) - 1);
+        let min_exp = 1 - Self::EXP_BIAS - Self::MANTISSA_BITS as i32;
+        if biased_exp == 0 {
+            (self.is_sign_negative(), fraction, min_exp)
+        } else {
+            (self.is_sign_negative(), fraction | (1 << Self::MANTISSA_BITS), min_exp + biased_exp - 1)
+        }
+    }
+}
+
+impl Float for f64 {
+    const NAME: &'static str = "f64";
+    const BITS: u32 = 64;
+    const MANTISSA_BITS: u32 = 52;
+    const EXP_BIAS: i32 = 1023;
+
+    #[inline]
+    fn to_bits_u64(self) -> u64 {
+        self.to_bits()
+    }
+
+    #[inline]
+    fn from_bits_u64(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+
+    #[inline]
+    fn from_f64(n: f64) -> Self {
+        n
+    }
+
+    #[inline]
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+
+    #[inline]
+    fn is_normal(self) -> bool {
+        f64::is_normal(self)
+    }
+
+    #[inline]
+    fn is_sign_negative(self) -> bool {
+        f64::is_sign_negative(self)
+    }
+
+    #[inline]
+    fn round(self) -> Self {
+        f64::round(self)
+    }
+
+    #[inline]
+    fn trunc(self) -> Self {
+        f64::trunc(self)
+    }
+
+    #[inline]
+    fn next_up(self) -> Self {
+        f64::next_up(self)
+    }
+
+    #[inline]
+    fn next_down(self) -> Self {
+        f64::next_down(self)
+    }
+}
+
+impl Float for f32 {
+    const NAME: &'static str = "f32";
+    const BITS: u32 = 32;
+    const MANTISSA_BITS: u32 = 23;
+    const EXP_BIAS: i32 = 127;
+
+    #[inline]
+    fn to_bits_u64(self) -> u64 {
+        self.to_bits() as u64
+    }
+
+    #[inline]
+    fn from_bits_u64(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+
+    #[inline]
+    fn from_f64(n: f64) -> Self {
+        n as f32
+    }
+
+    #[inline]
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+
+    #[inline]
+    fn is_normal(self) -> bool {
+        f32::is_normal(self)
+    }
+
+    #[inline]
+    fn is_sign_negative(self) -> bool {
+        f32::is_sign_negative(self)
+    }
+
+    #[inline]
+    fn round(self) -> Self {
+        f32::round(self)
+    }
+
+    #[inline]
+    fn trunc(self) -> Self {
+        f32::trunc(self)
+    }
+
+    #[inline]
+    fn next_up(self) -> Self {
+        f32::next_up(self)
+    }
+
+    #[inline]
+    fn next_down(self) -> Self {
+        f32::next_down(self)
+    }
+}
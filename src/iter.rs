@@ -0,0 +1,812 @@
+// Iteration through the decimal values to test.
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+
+//==============================================================================
+// Iteration through floating-point values (string representation)
+//------------------------------------------------------------------------------
+
+/// Maximum number of fractional digits, so that the number of values fits in an u64.
+pub const MAX_DEPTH: usize = 18;
+
+/// Iterator through the string representation of decimal values ending with a tie digit,
+/// and the precision at which they must be rounded: `("0.5", 0)`, `("0.05", 1)`,
+/// `("0.005", 2)`, ... `("0.015", 2)`, ... up to the maximum number of fractional digits.
+///
+/// The values have an index, which gives a random access to the sequence (see
+/// [RoundTestIter::value_at] and [RoundTestIter::index_of]), and allows to iterate through
+/// a range of indices.
+///
+/// The integer part is 0 by default, but other integer parts can be given with
+/// [RoundTestIter::with_integers]; all the fractional parts are generated for each of them.
+/// Similarly, the values end with the tie digit 5 by default, but other endings can be given
+/// with a [TiePattern] in [RoundTestIter::with_pattern].
+///
+/// ```
+/// use rounding::RoundTestIter;
+///
+/// let values = RoundTestIter::new(2, false).collect::<Vec<_>>();
+/// assert_eq!(values[..3], [("0.5".to_string(), 0), ("0.05".to_string(), 1), ("0.15".to_string(), 1)]);
+/// assert_eq!(values.len(), 11);
+/// ```
+pub struct RoundTestIter {
+    /// digits before the ending of the next value
+    digits: Vec<u8>,
+    /// index of the ending of the next value in `pattern`
+    ending: usize,
+    max: usize,
+    negative: bool,
+    /// integer parts of the values
+    ints: IntegerParts,
+    /// endings of the values
+    pattern: TiePattern,
+    /// number of values for each integer part
+    per_int: u64,
+    /// sign and integer part of the next value, with the decimal point
+    root: String,
+    /// index of the next value
+    index: u64,
+    /// index after the last value
+    end: u64
+}
+
+impl RoundTestIter {
+    /// Creates an iterator through all the values with 1 to `max` fractional digits.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: generates negative values instead of positive ones
+    pub fn new(max: usize, negative: bool) -> RoundTestIter {
+        RoundTestIter::with_range(max, negative, 0..RoundTestIter::total(max))
+    }
+
+    /// Creates an iterator through the values whose fractional part starts with `prefix` and
+    /// has more digits than `prefix`, up to `max`. The values are in the same order as in
+    /// [RoundTestIter::new], so `with_prefix(max, negative, p)` yields the same values as
+    /// `with_prefix(p.len() + 1, negative, p)` followed by `with_prefix(max, negative, p + d)`
+    /// for each digit `d` from 0 to 9.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `prefix`: first digits of the fractional part
+    ///
+    /// ```
+    /// use rounding::RoundTestIter;
+    ///
+    /// let values = RoundTestIter::with_prefix(3, false, "4").map(|(s, _)| s).collect::<Vec<_>>();
+    /// assert_eq!(values[..3], ["0.45", "0.405", "0.415"]);
+    /// assert_eq!(values.len(), 11);
+    /// ```
+    pub fn with_prefix(max: usize, negative: bool, prefix: &str) -> RoundTestIter {
+        debug_assert!(prefix.bytes().all(|d| d.is_ascii_digit()), "invalid prefix: {prefix}");
+        let digits = prefix.bytes().map(|d| d - b'0').collect::<Vec<_>>();
+        let start = node_index(max, 1, &digits);
+        RoundTestIter::with_range(max, negative, start..start + subtree_nodes(max, digits.len()))
+    }
+
+    /// Creates an iterator through the values from index `start` to the end.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `start`: index of the first value
+    pub fn from_index(max: usize, negative: bool, start: u64) -> RoundTestIter {
+        RoundTestIter::with_range(max, negative, start..RoundTestIter::total(max))
+    }
+
+    /// Creates an iterator through the values of a range of indices.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `range`: range of indices, which is limited to the total number of values
+    ///
+    /// ```
+    /// use rounding::RoundTestIter;
+    ///
+    /// let mut it = RoundTestIter::with_range(3, false, 20..30);
+    /// assert_eq!(it.len(), 10);
+    /// assert_eq!(it.next(), Some(("0.175".to_string(), 2)));
+    /// assert_eq!(it.nth(3), Some(("0.205".to_string(), 2)));
+    /// assert_eq!(it.last(), Some(("0.255".to_string(), 2)));
+    /// ```
+    pub fn with_range(max: usize, negative: bool, range: Range<u64>) -> RoundTestIter {
+        RoundTestIter::with_integers(max, negative, IntegerParts::default(), range)
+    }
+
+    /// Creates an iterator through the values of a range of indices, with the integer parts
+    /// `ints`. The values of each integer part are in the same order as in [RoundTestIter::new],
+    /// and the integer parts are in the order of `ints`.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `ints`: integer parts of the values
+    /// * `range`: range of indices, which is limited to the total number of values (see
+    ///   [RoundTestIter::total_with])
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use rounding::{IntegerParts, RoundTestIter};
+    ///
+    /// let ints = IntegerParts::from_str("1,99999").unwrap();
+    /// let values = RoundTestIter::with_integers(3, false, ints, 0..u64::MAX).map(|(s, _)| s).collect::<Vec<_>>();
+    /// assert_eq!(values.len(), 222);
+    /// assert_eq!(values[..3], ["1.5", "1.05", "1.005"]);
+    /// assert_eq!(values[110..112], ["1.995", "99999.5"]);
+    /// assert_eq!(values[221], "99999.995");
+    /// ```
+    pub fn with_integers(max: usize, negative: bool, ints: IntegerParts, range: Range<u64>) -> RoundTestIter {
+        RoundTestIter::with_pattern(max, negative, ints, TiePattern::default(), range)
+    }
+
+    /// Creates an iterator through the values of a range of indices, with the integer parts
+    /// `ints` and the endings of `pattern`. Each string of digits before the ending is followed
+    /// by all the endings, in the order of `pattern`; the precision is the number of fractional
+    /// digits before the ending, from 0 to `max - 1`.
+    ///
+    /// * `max`: maximum number of fractional digits before the ending, plus one
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `ints`: integer parts of the values
+    /// * `pattern`: endings of the values
+    /// * `range`: range of indices, which is limited to the total number of values (see
+    ///   [RoundTestIter::total_with])
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use rounding::{IntegerParts, RoundTestIter, TiePattern};
+    ///
+    /// let pattern = TiePattern::from_str("49{3},50{3}1").unwrap();
+    /// let mut it = RoundTestIter::with_pattern(2, false, IntegerParts::default(), pattern, 0..u64::MAX);
+    /// assert_eq!(it.len(), 22);
+    /// assert_eq!(it.next(), Some(("0.4999".to_string(), 0)));
+    /// assert_eq!(it.next(), Some(("0.50001".to_string(), 0)));
+    /// assert_eq!(it.next(), Some(("0.04999".to_string(), 1)));
+    /// ```
+    pub fn with_pattern(max: usize, negative: bool, ints: IntegerParts, pattern: TiePattern, range: Range<u64>) -> RoundTestIter {
+        assert!(max <= MAX_DEPTH, "max must be at most {MAX_DEPTH}");
+        let end = range.end.min(RoundTestIter::total_with(max, &ints, &pattern));
+        let mut it = RoundTestIter {
+            digits: Vec::with_capacity(max),
+            ending: 0,
+            max,
+            negative,
+            per_int: pattern.len() as u64 * subtree_nodes(max, 0),
+            ints,
+            pattern,
+            root: String::new(),
+            index: 0,
+            end,
+        };
+        it.seek(range.start);
+        it
+    }
+
+    /// Total number of values with 1 to `max` fractional digits, for one integer part.
+    pub fn total(max: usize) -> u64 {
+        subtree_nodes(max, 0)
+    }
+
+    /// Total number of values for all the integer parts `ints` and the endings of `pattern`
+    /// (see [RoundTestIter::with_pattern]), or `u64::MAX` if there are more values than that.
+    pub fn total_with(max: usize, ints: &IntegerParts, pattern: &TiePattern) -> u64 {
+        subtree_nodes(max, 0).saturating_mul(pattern.len() as u64).saturating_mul(ints.len())
+    }
+
+    /// Value of index `index` and its precision, or `None` if the index is out of range.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `negative`: negative value instead of positive one
+    /// * `index`: index of the value
+    ///
+    /// ```
+    /// use rounding::RoundTestIter;
+    ///
+    /// assert_eq!(RoundTestIter::value_at(3, true, 23), Some(("-0.25".to_string(), 1)));
+    /// assert_eq!(RoundTestIter::index_of(3, "-0.25"), Some(23));
+    /// ```
+    pub fn value_at(max: usize, negative: bool, index: u64) -> Option<(String, usize)> {
+        RoundTestIter::with_range(max, negative, index..index + 1).next()
+    }
+
+    /// Index of `value` in the sequence, or `None` if it's not in it. The sign is ignored.
+    ///
+    /// * `max`: maximum number of fractional digits
+    /// * `value`: string representation of the value, as generated by the iterator
+    pub fn index_of(max: usize, value: &str) -> Option<u64> {
+        let frac = value.strip_prefix('-').unwrap_or(value).strip_prefix("0.")?;
+        let digits = frac.strip_suffix('5')?;
+        if frac.len() > max || !digits.bytes().all(|d| d.is_ascii_digit()) {
+            return None;
+        }
+        let digits = digits.bytes().map(|d| d - b'0').collect::<Vec<_>>();
+        Some(node_index(max, 1, &digits))
+    }
+
+    /// Index of the next value.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Scales each value by `10^k` for all the `k` of `scales`, in that order, by appending
+    /// the exponent `ek` to its representation. The precision is reduced by `k`, to keep the same
+    /// tie digit, and the scaled values whose precision would be negative are skipped.
+    ///
+    /// ```
+    /// use rounding::RoundTestIter;
+    ///
+    /// let values = RoundTestIter::new(2, false).scaled(-2..=1).collect::<Vec<_>>();
+    /// assert_eq!(values[..4], [("0.5e-2".to_string(), 2), ("0.5e-1".to_string(), 1),
+    ///                          ("0.5".to_string(), 0), ("0.05e-2".to_string(), 3)]);
+    /// assert_eq!(values[6..8], [("0.05e1".to_string(), 0), ("0.15e-2".to_string(), 3)]);
+    /// ```
+    pub fn scaled(self, scales: RangeInclusive<i32>) -> impl Iterator<Item = (String, usize)> {
+        scaled(self, scales)
+    }
+
+    /// Moves the iterator to the value of index `index`, or to the end if `index` is out of range.
+    fn seek(&mut self, index: u64) {
+        self.index = index.min(self.end);
+        if self.index < self.end {
+            let int = self.ints.get(self.index / self.per_int);
+            let (digits, ending) = node_at(self.max, self.pattern.len(), self.index % self.per_int);
+            self.root = format!("{}{int}.", if self.negative { "-" } else { "" });
+            self.digits = digits;
+            self.ending = ending;
+        }
+    }
+}
+
+/// Number of digit strings with `len` to `max - 1` digits that start with a given string of
+/// `len` digits (including itself); each of them is followed by all the endings in the values.
+fn subtree_nodes(max: usize, len: usize) -> u64 {
+    if len >= max { 0 } else { (10_u64.pow((max - len) as u32) - 1) / 9 }
+}
+
+/// Index of the first value starting with `digits`, when there are `endings` endings.
+fn node_index(max: usize, endings: usize, digits: &[u8]) -> u64 {
+    let endings = endings as u64;
+    digits.iter().enumerate()
+        .map(|(i, &d)| endings + d as u64 * endings * subtree_nodes(max, i + 1))
+        .sum()
+}
+
+/// Digits and index of the ending of the value of index `index`, when there are `endings` endings.
+fn node_at(max: usize, endings: usize, mut index: u64) -> (Vec<u8>, usize) {
+    let endings = endings as u64;
+    let mut digits = Vec::with_capacity(max);
+    while index >= endings {
+        index -= endings;
+        let child = endings * subtree_nodes(max, digits.len() + 1);
+        digits.push((index / child) as u8);
+        index %= child;
+    }
+    (digits, index as usize)
+}
+
+/// The digit strings are visited in depth-first order: each of them is followed by all the
+/// endings, then by the digit strings that start with it.
+impl Iterator for RoundTestIter {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
+        }
+        let ending = &self.pattern.0[self.ending];
+        let mut value = String::with_capacity(self.root.len() + self.digits.len() + ending.len());
+        value.push_str(&self.root);
+        value.extend(self.digits.iter().map(|&d| (b'0' + d) as char));
+        value.push_str(ending);
+        let result = Some((value, self.digits.len()));
+        self.index += 1;
+        self.ending += 1;
+        if self.ending == self.pattern.len() {
+            self.ending = 0;
+            if self.digits.len() + 1 < self.max {
+                self.digits.push(0);
+            } else {
+                while self.digits.last() == Some(&9) {
+                    self.digits.pop();
+                }
+                match self.digits.last_mut() {
+                    Some(d) => *d += 1,
+                    // next integer part
+                    None => self.seek(self.index)
+                }
+            }
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.index) as usize;
+        (len, Some(len))
+    }
+
+    /// Skips `n` values in O(max).
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.seek(self.index.saturating_add(n as u64));
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for RoundTestIter {}
+
+//==============================================================================
+// Iteration through the values with carry propagation
+//------------------------------------------------------------------------------
+
+/// Iterator through the values whose rounding propagates a carry through all their digits, and
+/// the precision at which they must be rounded: an integer part of 0 or a run of 9s, then a
+/// fractional part made of a run of 9s followed by 5, like `9.995` or `999.9995`. Rounded away
+/// from zero, they need a new leading digit: `10.00`, `1000.000`.
+///
+/// The values are sorted by length of the integer part, then by length of the fractional part,
+/// and they have an index like the values of [RoundTestIter].
+///
+/// ```
+/// use rounding::CarryIter;
+///
+/// let values = CarryIter::new(3, true).map(|(s, _)| s).collect::<Vec<_>>();
+/// assert_eq!(values[..4], ["-0.5", "-0.95", "-0.995", "-9.5"]);
+/// assert_eq!(values.len(), 12);
+/// assert_eq!(values[11], "-999.995");
+/// ```
+pub struct CarryIter {
+    max: usize,
+    negative: bool,
+    /// index of the next value
+    index: u64,
+    /// index after the last value
+    end: u64
+}
+
+impl CarryIter {
+    /// Creates an iterator through all the values with 0 to `max` integer digits (0 being the
+    /// integer part "0") and 1 to `max` fractional digits.
+    ///
+    /// * `max`: maximum number of digits in the integer part and in the fractional part
+    /// * `negative`: generates negative values instead of positive ones
+    pub fn new(max: usize, negative: bool) -> CarryIter {
+        CarryIter::with_range(max, negative, 0..CarryIter::total(max))
+    }
+
+    /// Creates an iterator through the values of a range of indices.
+    ///
+    /// * `max`: maximum number of digits in the integer part and in the fractional part
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `range`: range of indices, which is limited to the total number of values
+    pub fn with_range(max: usize, negative: bool, range: Range<u64>) -> CarryIter {
+        let end = range.end.min(CarryIter::total(max));
+        CarryIter { max, negative, index: range.start.min(end), end }
+    }
+
+    /// Total number of values for `max` digits.
+    pub fn total(max: usize) -> u64 {
+        (max as u64 + 1) * max as u64
+    }
+
+    /// Index of the next value.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl Iterator for CarryIter {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
+        }
+        let int_len = (self.index / self.max as u64) as usize;
+        let pr = (self.index % self.max as u64) as usize;
+        self.index += 1;
+        let mut value = String::with_capacity(int_len + pr + 4);
+        if self.negative {
+            value.push('-');
+        }
+        if int_len == 0 {
+            value.push('0');
+        }
+        value.extend(std::iter::repeat_n('9', int_len));
+        value.push('.');
+        value.extend(std::iter::repeat_n('9', pr));
+        value.push('5');
+        Some((value, pr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.index) as usize;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.saturating_add(n as u64).min(self.end);
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for CarryIter {}
+
+//==============================================================================
+// Iteration through the exactly representable ties
+//------------------------------------------------------------------------------
+
+/// Iterator through the binary values which are exactly halfway between two decimal values with
+/// `pr` fractional digits, and that precision `pr`: `("0.5", 0)`, `("0.25", 1)`, `("0.75", 1)`,
+/// `("0.125", 2)`, ... `("2.5", 0)`, ... Unlike most values of [RoundTestIter], like `0.15`, they
+/// are genuine ties once parsed, so their rounding depends on the tie-breaking rule.
+///
+/// A decimal tie `(2k + 1) * 5 / 10^(pr + 1)` is a binary value if and only if it's an odd
+/// multiple of `2^-(pr + 1)`, so the fractional parts with `pr + 1` digits are the `2^pr` values
+/// `q / 2^(pr + 1)` for the odd `q` below `2^(pr + 1)`. They are generated for each integer part
+/// of an [IntegerParts], by increasing precision then value, and they have an index like the
+/// values of [RoundTestIter]. The values which need more significant bits than the tested type,
+/// which wouldn't be ties any more once parsed, are skipped.
+///
+/// ```
+/// use rounding::{ExactTieIter, IntegerParts};
+///
+/// let values = ExactTieIter::new(3, false).map(|(s, _)| s).collect::<Vec<_>>();
+/// assert_eq!(values, ["0.5", "0.25", "0.75", "0.125", "0.375", "0.625", "0.875"]);
+///
+/// // 2^53 + 0.5 needs 55 bits
+/// let ints = "2,9007199254740992".parse::<IntegerParts>().unwrap();
+/// let values = ExactTieIter::with_integers(2, true, ints, 53, 0..6).map(|(s, _)| s).collect::<Vec<_>>();
+/// assert_eq!(values, ["-2.5", "-2.25", "-2.75"]);
+/// ```
+pub struct ExactTieIter {
+    max: usize,
+    negative: bool,
+    ints: IntegerParts,
+    /// number of bits of the significand of the tested type
+    bits: u32,
+    /// index of the next value
+    index: u64,
+    /// index after the last value
+    end: u64
+}
+
+impl ExactTieIter {
+    /// Creates an iterator through all the ties with 1 to `max` fractional digits, with the
+    /// integer part 0, which are exact in `f64`.
+    ///
+    /// * `max`: maximum number of digits in the fractional part
+    /// * `negative`: generates negative values instead of positive ones
+    pub fn new(max: usize, negative: bool) -> ExactTieIter {
+        let ints = IntegerParts::default();
+        let total = ExactTieIter::total(max, &ints);
+        ExactTieIter::with_integers(max, negative, ints, 53, 0..total)
+    }
+
+    /// Creates an iterator through the ties of a range of indices, for several integer parts.
+    ///
+    /// * `max`: maximum number of digits in the fractional part, up to [MAX_DEPTH]
+    /// * `negative`: generates negative values instead of positive ones
+    /// * `ints`: integer parts of the values
+    /// * `bits`: number of bits of the significand of the tested type (53 for `f64`, 24 for `f32`);
+    ///   the values which need more bits are skipped
+    /// * `range`: range of indices, which is limited to the total number of values
+    pub fn with_integers(max: usize, negative: bool, ints: IntegerParts, bits: u32, range: Range<u64>) -> ExactTieIter {
+        assert!(max <= MAX_DEPTH, "max depth {max} is greater than {MAX_DEPTH}");
+        let end = range.end.min(ExactTieIter::total(max, &ints));
+        ExactTieIter { max, negative, ints, bits, index: range.start.min(end), end }
+    }
+
+    /// Total number of indices for `max` digits and the integer parts `ints`, including the
+    /// values which are skipped because they need too many bits, or `u64::MAX` if there are
+    /// more indices than that.
+    pub fn total(max: usize, ints: &IntegerParts) -> u64 {
+        ((1_u64 << max) - 1).saturating_mul(ints.len())
+    }
+
+    /// Index of the next value.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+}
+
+impl Iterator for ExactTieIter {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let per_int = (1_u64 << self.max) - 1;
+        while self.index < self.end {
+            let int = self.ints.get(self.index / per_int);
+            // the values of precision pr have the offsets 2^pr - 1 to 2^(pr + 1) - 2
+            let offset = self.index % per_int + 1;
+            self.index += 1;
+            let pr = offset.ilog2() as usize;
+            let q = 2 * (offset - (1 << pr)) + 1;
+            // the significand (int * 2^(pr + 1) + q) is odd, so it must fit in the bits
+            if ((int as u128) << (pr + 1) | q as u128) >> self.bits != 0 {
+                continue;
+            }
+            // q / 2^(pr + 1) = q * 5^(pr + 1) / 10^(pr + 1)
+            let fraction = q * 5_u64.pow(pr as u32 + 1);
+            let sign = if self.negative { "-" } else { "" };
+            return Some((format!("{sign}{int}.{fraction:0width$}", width = pr + 1), pr));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.end - self.index) as usize))
+    }
+}
+
+//==============================================================================
+// Endings of the values
+//------------------------------------------------------------------------------
+
+/// Endings of the values generated by [RoundTestIter::with_pattern], which are given as a
+/// comma-separated list of digit strings. A digit followed by `{n}` is repeated `n` times, so
+/// that runs of digits can probe the values just below and above a tie, like `49{5}` for
+/// `499999` and `50{4}1` for `500001`.
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::TiePattern;
+///
+/// let pattern = TiePattern::from_str("4,5,6,49{3},50{2}1").unwrap();
+/// assert_eq!(pattern.endings(), ["4", "5", "6", "4999", "5001"]);
+/// assert_eq!(pattern.to_string(), "4,5,6,4999,5001");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TiePattern(Vec<String>);
+
+impl TiePattern {
+    /// Endings of the values.
+    pub fn endings(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Number of endings.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there is no ending.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The single ending `5`, which gives the ties.
+impl Default for TiePattern {
+    fn default() -> Self {
+        TiePattern(vec!["5".to_string()])
+    }
+}
+
+/// Parses a list of endings (see [TiePattern]).
+impl FromStr for TiePattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let endings = s.split(',').map(|item| {
+            let invalid = || format!("invalid ending '{item}'");
+            let mut ending = String::new();
+            let mut chars = item.trim().chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '0'..='9' => ending.push(c),
+                    '{' => {
+                        let (count, rest) = chars.as_str().split_once('}').ok_or_else(invalid)?;
+                        let count = usize::from_str(count).map_err(|_| invalid())?;
+                        chars = rest.chars();
+                        let digit = ending.pop().ok_or_else(invalid)?;
+                        ending.extend(std::iter::repeat_n(digit, count));
+                    }
+                    _ => return Err(invalid())
+                }
+            }
+            if ending.is_empty() { Err(invalid()) } else { Ok(ending) }
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(TiePattern(endings))
+    }
+}
+
+impl Display for TiePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(","))
+    }
+}
+
+/// Scales the values of `values` by `10^k` for all the `k` of `scales` (see [RoundTestIter::scaled]).
+pub fn scaled<I>(values: I, scales: RangeInclusive<i32>) -> impl Iterator<Item = (String, usize)>
+    where I: Iterator<Item = (String, usize)>
+{
+    values.flat_map(move |(value, pr)| {
+        scales.clone()
+            .filter(move |&k| k <= pr as i32)
+            .map(move |k| match k {
+                0 => (value.clone(), pr),
+                k => (format!("{value}e{k}"), (pr as i32 - k) as usize)
+            })
+    })
+}
+
+/// Greatest absolute value of the scales parsed by [parse_scales], which covers the range of `f64`
+/// (from 4.9e-324 to 1.8e308) at any depth.
+pub const MAX_SCALE: i32 = 400;
+
+/// Parses a range of scales for [RoundTestIter::scaled], as `k`, `a..b` or `a..=b`, within
+/// `-MAX_SCALE..=MAX_SCALE`.
+///
+/// ```
+/// use rounding::parse_scales;
+///
+/// assert_eq!(parse_scales("-300..=300"), Ok(-300..=300));
+/// assert_eq!(parse_scales("-3..0"), Ok(-3..=-1));
+/// assert!(parse_scales("3..1").is_err());
+/// assert!(parse_scales("-2147483648..=2147483647").is_err());
+/// ```
+pub fn parse_scales(s: &str) -> Result<RangeInclusive<i32>, String> {
+    let invalid = || format!("invalid scales '{s}'");
+    let parse = |k: &str| i32::from_str(k.trim()).map_err(|_| invalid());
+    let range = match s.split_once("..=") {
+        Some((a, b)) => parse(a)?..=parse(b)?,
+        None => match s.split_once("..") {
+            Some((a, b)) => parse(a)?..=parse(b)?.checked_sub(1).ok_or_else(invalid)?,
+            None => parse(s)?..=parse(s)?
+        }
+    };
+    if range.is_empty() || *range.start() < -MAX_SCALE || *range.end() > MAX_SCALE {
+        Err(invalid())
+    } else {
+        Ok(range)
+    }
+}
+
+//==============================================================================
+// Integer parts of the values
+//------------------------------------------------------------------------------
+
+/// Integer parts of the values generated by [RoundTestIter::with_integers], which are given as
+/// a comma-separated list of items:
+/// - `n`: a single value
+/// - `a..b` or `a..=b`: a range of values
+/// - `10^a..=10^b`: a magnitude sweep, with the values `10^k - 1` and `10^k` for each `k` from `a`
+///   to `b` (`0`, `1`, `9`, `10`, `99`, `100`, ...)
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::IntegerParts;
+///
+/// let ints = IntegerParts::from_str("0,2..4,10^1..=10^2").unwrap();
+/// assert_eq!(ints.iter().collect::<Vec<_>>(), [0, 2, 3, 9, 10, 99, 100]);
+/// assert_eq!(ints.to_string(), "0,2..=3,10^1..=10^2");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegerParts(Vec<IntItem>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum IntItem {
+    Range(RangeInclusive<u64>),
+    /// Exponents of a magnitude sweep
+    Sweep(RangeInclusive<u32>)
+}
+
+impl IntItem {
+    /// Number of integer parts, or `None` if it doesn't fit in a `u64`.
+    fn len(&self) -> Option<u64> {
+        match self {
+            IntItem::Range(r) if r.start() <= r.end() => (r.end() - r.start()).checked_add(1),
+            IntItem::Sweep(r) if r.start() <= r.end() => Some(2 * (r.end() - r.start() + 1) as u64),
+            _ => Some(0)
+        }
+    }
+
+    fn get(&self, index: u64) -> u64 {
+        match self {
+            IntItem::Range(r) => r.start() + index,
+            IntItem::Sweep(r) => 10_u64.pow(r.start() + (index / 2) as u32) - (1 - index % 2),
+        }
+    }
+}
+
+impl IntegerParts {
+    /// Number of integer parts.
+    pub fn len(&self) -> u64 {
+        // checked by IntegerParts::from_str
+        self.checked_len().unwrap_or(u64::MAX)
+    }
+
+    /// Number of integer parts, or `None` if it doesn't fit in a `u64`.
+    fn checked_len(&self) -> Option<u64> {
+        self.0.iter().try_fold(0_u64, |n, item| n.checked_add(item.len()?))
+    }
+
+    /// Returns true if there is no integer part.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Integer part of index `index`, which must be lower than [IntegerParts::len].
+    pub fn get(&self, mut index: u64) -> u64 {
+        for item in &self.0 {
+            let len = item.len().unwrap_or(u64::MAX);
+            if index < len {
+                return item.get(index);
+            }
+            index -= len;
+        }
+        panic!("integer part index out of range")
+    }
+
+    /// Iterates through the integer parts.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+}
+
+/// The single integer part 0.
+impl Default for IntegerParts {
+    fn default() -> Self {
+        IntegerParts(vec![IntItem::Range(0..=0)])
+    }
+}
+
+/// Parses a list of integer parts (see [IntegerParts]).
+impl FromStr for IntegerParts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |item: &str| format!("invalid integer parts '{item}'");
+        let parse = |item: &str, n: &str| u64::from_str(n.trim()).map_err(|_| invalid(item));
+        let parse_exp = |item: &str, n: &str| match n.trim().strip_prefix("10^").map(u32::from_str) {
+            Some(Ok(k)) if k <= 19 => Ok(k),
+            _ => Err(invalid(item))
+        };
+        let items = s.split(',').map(|item| {
+            let (start, end, inclusive) = match item.split_once("..=") {
+                Some((start, end)) => (start, Some(end), true),
+                None => match item.split_once("..") {
+                    Some((start, end)) => (start, Some(end), false),
+                    None => (item, None, true)
+                }
+            };
+            match end {
+                None => parse(item, start).map(|n| IntItem::Range(n..=n)),
+                Some(end) if start.contains('^') => {
+                    let (a, b) = (parse_exp(item, start)?, parse_exp(item, end)?);
+                    match inclusive {
+                        true => Ok(IntItem::Sweep(a..=b)),
+                        false if b > a => Ok(IntItem::Sweep(a..=b - 1)),
+                        false => Err(invalid(item))
+                    }
+                }
+                Some(end) => {
+                    let (a, b) = (parse(item, start)?, parse(item, end)?);
+                    match inclusive {
+                        true => Ok(IntItem::Range(a..=b)),
+                        false if b > a => Ok(IntItem::Range(a..=b - 1)),
+                        false => Err(invalid(item))
+                    }
+                }
+            }
+        }).collect::<Result<Vec<_>, _>>()?;
+        let ints = IntegerParts(items);
+        match ints.checked_len() {
+            Some(_) => Ok(ints),
+            None => Err(format!("too many integer parts in '{s}'")),
+        }
+    }
+}
+
+impl Display for IntegerParts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            match item {
+                IntItem::Range(r) if r.start() == r.end() => write!(f, "{}", r.start())?,
+                IntItem::Range(r) => write!(f, "{}..={}", r.start(), r.end())?,
+                IntItem::Sweep(r) => write!(f, "10^{}..=10^{}", r.start(), r.end())?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,433 @@
+//! Detection of rounding discrepancies in the floating-point implementation of
+//! `Display::fmt` `"{:.prec$}"`, and reference rounding functions.
+//!
+//! - [str_sround], [float_sround], [f64_sround] and [f32_sround] round the decimal representation of a value under a [Policy]
+//! - [str_sround_sig], [float_sround_sig], [f64_sround_sig] and [f32_sround_sig] round it to a number of significant digits
+//! - [str_sround_at] rounds it at a signed digit position, like the hundreds for -2
+//! - [str_sround_exp] rounds it in scientific notation, like `format!("{:.pr$e}")`
+//! - [Round] rounds a floating-point value to a number of fractional digits under a [Policy], correctly
+//! - [BigDecimal] holds the exact decimal value of a [Float], for exact reference computations
+//! - [Float] abstracts the IEEE 754 binary types, `f64` and `f32`
+//! - [RoundTestIter] generates the decimal values ending with a tie digit, or the endings of a
+//!   [TiePattern], for the [IntegerParts], and scaled by powers of 10 with [RoundTestIter::scaled]
+//! - [CarryIter] generates the decimal values whose rounding propagates a carry, like `9.995`
+//! - [ExactTieIter] generates the binary values which are exact decimal ties, like `0.125`
+//! - [scan::find_issues] compares `Display::fmt` with the reference roundings, and can be resumed
+//!   from a [checkpoint::Checkpoint]
+//! - [random::SplitMix64] draws reproducible random samples of the values to scan
+//! - [baseline::Baseline] records the discrepancies of the scans, to compare them between runs
+//! - [explain::explain] details the rounding of a single value by `Display::fmt`
+//! - [report::write_report] writes the results of the scans as a JUnit XML or TAP test report
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub mod baseline;
+pub mod checkpoint;
+pub mod decimal;
+pub mod explain;
+mod float;
+mod iter;
+pub mod random;
+pub mod report;
+pub mod scan;
+mod tests;
+
+pub use decimal::BigDecimal;
+pub use float::Float;
+pub use iter::{parse_scales, scaled, CarryIter, ExactTieIter, IntegerParts, RoundTestIter, TiePattern, MAX_SCALE};
+
+//==============================================================================
+// Simple and naive rounding
+//------------------------------------------------------------------------------
+
+/// Rounding of a floating-point value to a number of fractional digits, computed with
+/// floating-point operations (so not always correct). It's only kept for comparison with
+/// [Round], see [scan::compare_round].
+pub trait NaiveRound {
+    /// Rounds to the nearest value with `pr` fractional digits, half away from zero.
+    fn naive_round_digit(self, pr: usize) -> Self;
+    /// Truncates to `pr` fractional digits.
+    fn naive_trunc_digit(self, pr: usize) -> Self;
+}
+
+impl<T: Float> NaiveRound for T {
+    #[inline]
+    fn naive_round_digit(self, pr: usize) -> T {
+        let n = T::from_f64(pow10(pr as i32));
+        (self * n).round() / n
+    }
+
+    #[inline]
+    fn naive_trunc_digit(self, pr: usize) -> T {
+        let n = T::from_f64(pow10(pr as i32));
+        (self * n).trunc() / n
+    }
+}
+
+fn pow10(n: i32) -> f64 {
+    match n {
+        0 => 1.0,
+        1 => 10.0,
+        2 => 100.0,
+        3 => 1000.0,
+        4 => 10000.0,
+        5 => 100000.0,
+        6 => 1000000.0,
+        7 => 10000000.0,
+        8 => 100000000.0,
+        9 => 1000000000.0,
+        10 => 10000000000.0,
+        11 => 100000000000.0,
+        n => 10.0_f64.powi(n)
+    }
+}
+
+//==============================================================================
+// String-based rounding (for comparison)
+//------------------------------------------------------------------------------
+
+/// Rounding policy. The examples show the rounding to an integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// To the nearest, ties to the even digit (2.5 -> 2, 3.5 -> 4)
+    ToEven,
+    /// To the nearest, ties away from zero (2.5 -> 3, -2.5 -> -3)
+    AwayFromZero,
+    /// Toward zero, or truncation (2.7 -> 2, -2.7 -> -2)
+    TowardZero,
+    /// Toward -inf (2.7 -> 2, -2.2 -> -3)
+    Floor,
+    /// Toward +inf (2.2 -> 3, -2.7 -> -2)
+    Ceiling,
+    /// To the nearest, ties toward zero (2.5 -> 2, -2.5 -> -2)
+    HalfTowardZero,
+    /// To the nearest, ties toward -inf (2.5 -> 2, -2.5 -> -3)
+    HalfDown,
+    /// To the nearest, ties toward +inf (2.5 -> 3, -2.5 -> -2)
+    HalfUp,
+    /// To the nearest, ties to the odd digit (2.5 -> 3, 3.5 -> 3)
+    HalfOdd,
+    /// Toward zero, unless the last kept digit is 0 or 5, in which case away from zero
+    /// (2.7 -> 2, 5.2 -> 6, 0.2 -> 1). Rounding again with another policy to a lower
+    /// precision then gives the same result as rounding the original value directly.
+    ZeroFiveUp
+}
+
+impl Policy {
+    /// All the policies.
+    pub const ALL: [Policy; 10] = [
+        Policy::ToEven, Policy::AwayFromZero, Policy::TowardZero, Policy::Floor, Policy::Ceiling,
+        Policy::HalfTowardZero, Policy::HalfDown, Policy::HalfUp, Policy::HalfOdd, Policy::ZeroFiveUp
+    ];
+
+    /// Name of the policy, as parsed by [Policy::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Policy::ToEven => "even",
+            Policy::AwayFromZero => "away",
+            Policy::TowardZero => "zero",
+            Policy::Floor => "floor",
+            Policy::Ceiling => "ceiling",
+            Policy::HalfTowardZero => "half-zero",
+            Policy::HalfDown => "half-down",
+            Policy::HalfUp => "half-up",
+            Policy::HalfOdd => "half-odd",
+            Policy::ZeroFiveUp => "05up",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePolicyError(String);
+
+impl Display for ParsePolicyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown rounding policy '{}'", self.0)
+    }
+}
+
+impl Error for ParsePolicyError {}
+
+/// Parses the name of a policy (see [Policy::name]).
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::Policy;
+///
+/// assert_eq!(Policy::from_str("half-up"), Ok(Policy::HalfUp));
+/// assert!(Policy::from_str("half-sideways").is_err());
+/// ```
+impl FromStr for Policy {
+    type Err = ParsePolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Policy::ALL.into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| ParsePolicyError(s.to_string()))
+    }
+}
+
+/// Rounds the fractional part of `n` to `pr` digits, using [str_sround] to perform
+/// the rounding of its shortest decimal representation.
+///
+/// * `n`: floating-point value to round
+/// * `pr`: number of digits to keep in the fractional part
+/// * `policy`: rounding policy
+///
+/// ```
+/// use rounding::{float_sround, Policy};
+///
+/// assert_eq!(float_sround(2.95_f64, 1, &Policy::AwayFromZero), "3.0");
+/// assert_eq!(float_sround(-0.125_f32, 2, &Policy::ToEven), "-0.12");
+/// ```
+pub fn float_sround<T: Float>(n: T, pr: usize, policy: &Policy) -> String {
+    let s = n.to_string();
+    if !n.is_normal() {
+        s
+    } else {
+        str_sround(&s, pr, policy)
+    }
+}
+
+/// Rounds the fractional part of `n` to `pr` digits, see [float_sround].
+///
+/// ```
+/// use rounding::{f64_sround, Policy};
+///
+/// assert_eq!(f64_sround(2.95, 1, &Policy::AwayFromZero), "3.0");
+/// assert_eq!(f64_sround(-2.95, 1, &Policy::AwayFromZero), "-3.0");
+/// ```
+pub fn f64_sround(n: f64, pr: usize, policy: &Policy) -> String {
+    float_sround(n, pr, policy)
+}
+
+/// Rounds the fractional part of `n` to `pr` digits, see [float_sround].
+///
+/// ```
+/// use rounding::{f32_sround, Policy};
+///
+/// assert_eq!(f32_sround(2.95, 1, &Policy::AwayFromZero), "3.0");
+/// assert_eq!(f32_sround(-0.125, 2, &Policy::ToEven), "-0.12");
+/// ```
+pub fn f32_sround(n: f32, pr: usize, policy: &Policy) -> String {
+    float_sround(n, pr, policy)
+}
+
+/// Rounds the fractional part of `n` to `pr` digits, using the rounding `policy`. The rounding
+/// is made on the exact decimal value of the string with [BigDecimal::round].
+///
+/// * `n`: string representation of the floating-point value to round. Ideally the last non-null
+///   digit must be rounded properly (by default of anything better, a `format!("{:.}", f)` of the
+///   value - see [f64_sround], or the exact value - see [BigDecimal::from_f64]). If `n` isn't a
+///   valid decimal value, like "NaN" or "inf", it is returned unchanged.
+/// * `pr`: number of digits to keep in the fractional part
+///
+/// ```
+/// use rounding::{str_sround, Policy};
+///
+/// assert_eq!(str_sround("2.95", 1, &Policy::ToEven), "3.0");
+/// assert_eq!(str_sround("-2.95", 1, &Policy::ToEven), "-3.0");
+/// ```
+pub fn str_sround(n: &str, pr: usize, policy: &Policy) -> String {
+    match BigDecimal::from_str(n) {
+        Ok(value) => format!("{:.pr$}", value.round(pr, policy)),
+        Err(_) => n.to_string()
+    }
+}
+
+/// Rounds `n` at a signed digit `position`, using the rounding `policy`, like [str_sround] for
+/// the positive positions. A negative position rounds to a multiple of `10^-position`, and the
+/// result has no fractional part.
+///
+/// * `n`: string representation of the value to round (see [str_sround]); if it isn't a valid
+///   decimal value, like "NaN" or "inf", it is returned unchanged.
+/// * `position`: number of digits to keep in the fractional part, or opposite of the number of
+///   integer digits to clear
+///
+/// ```
+/// use rounding::{str_sround_at, Policy};
+///
+/// assert_eq!(str_sround_at("12345.6", -2, &Policy::ToEven), "12300");
+/// assert_eq!(str_sround_at("99950", -2, &Policy::ToEven), "100000");
+/// assert_eq!(str_sround_at("-2.95", 1, &Policy::ToEven), "-3.0");
+/// ```
+pub fn str_sround_at(n: &str, position: i32, policy: &Policy) -> String {
+    match BigDecimal::from_str(n) {
+        Ok(value) => {
+            let pr = position.max(0) as usize;
+            format!("{:.pr$}", value.round_at(position, policy))
+        }
+        Err(_) => n.to_string()
+    }
+}
+
+/// Rounds `n` to `digits` significant digits, using [str_sround_sig] to perform the rounding of
+/// its shortest decimal representation.
+///
+/// * `n`: floating-point value to round
+/// * `digits`: number of significant digits to keep, at least 1
+/// * `policy`: rounding policy
+///
+/// ```
+/// use rounding::{float_sround_sig, Policy};
+///
+/// assert_eq!(float_sround_sig(0.00012345_f64, 3, &Policy::ToEven), "0.000123");
+/// assert_eq!(float_sround_sig(123456_f32, 3, &Policy::ToEven), "123000");
+/// ```
+pub fn float_sround_sig<T: Float>(n: T, digits: usize, policy: &Policy) -> String {
+    let s = n.to_string();
+    if !n.is_normal() {
+        s
+    } else {
+        str_sround_sig(&s, digits, policy)
+    }
+}
+
+/// Rounds `n` to `digits` significant digits, see [float_sround_sig].
+///
+/// ```
+/// use rounding::{f64_sround_sig, Policy};
+///
+/// assert_eq!(f64_sround_sig(9.995, 3, &Policy::AwayFromZero), "10.0");
+/// ```
+pub fn f64_sround_sig(n: f64, digits: usize, policy: &Policy) -> String {
+    float_sround_sig(n, digits, policy)
+}
+
+/// Rounds `n` to `digits` significant digits, see [float_sround_sig].
+///
+/// ```
+/// use rounding::{f32_sround_sig, Policy};
+///
+/// assert_eq!(f32_sround_sig(-0.125, 2, &Policy::ToEven), "-0.12");
+/// ```
+pub fn f32_sround_sig(n: f32, digits: usize, policy: &Policy) -> String {
+    float_sround_sig(n, digits, policy)
+}
+
+/// Rounds `n` to `digits` significant digits, using the rounding `policy`, like [str_sround] for
+/// the fractional digits. The result is written in fixed notation, with the trailing zeros of the
+/// significant digits, and 0 is written with `digits - 1` fractional zeros.
+///
+/// * `n`: string representation of the value to round (see [str_sround]); if it isn't a valid
+///   decimal value, like "NaN" or "inf", it is returned unchanged.
+/// * `digits`: number of significant digits to keep, at least 1
+///
+/// ```
+/// use rounding::{str_sround_sig, Policy};
+///
+/// assert_eq!(str_sround_sig("0.00012345", 3, &Policy::ToEven), "0.000123");
+/// assert_eq!(str_sround_sig("123456", 3, &Policy::ToEven), "123000");
+/// assert_eq!(str_sround_sig("0.1", 3, &Policy::ToEven), "0.100");
+/// assert_eq!(str_sround_sig("-99.95", 3, &Policy::AwayFromZero), "-100");
+/// ```
+pub fn str_sround_sig(n: &str, digits: usize, policy: &Policy) -> String {
+    match BigDecimal::from_str(n) {
+        Ok(value) => {
+            let rounded = value.round_significant(digits, policy);
+            // the fractional digits among the significant ones, after a possible carry:
+            let lead = rounded.leading_exponent().unwrap_or(0);
+            let pr = (digits as i32 - 1 - lead).max(0) as usize;
+            format!("{rounded:.pr$}")
+        }
+        Err(_) => n.to_string()
+    }
+}
+
+/// Rounds `n` to `pr + 1` significant digits, using the rounding `policy`, and writes it in
+/// scientific notation like `format!("{:.pr$e}")`. If the rounding propagates a carry, the
+/// exponent is adjusted, like `9.995e3` which gives `1.00e4` at precision 2, away from zero.
+///
+/// * `n`: string representation of the value to round (see [str_sround]); if it isn't a valid
+///   decimal value, like "NaN" or "inf", it is returned unchanged.
+/// * `pr`: number of digits to keep after the leading digit
+///
+/// ```
+/// use rounding::{str_sround_exp, Policy};
+///
+/// assert_eq!(str_sround_exp("0.00012345", 2, &Policy::ToEven), "1.23e-4");
+/// assert_eq!(str_sround_exp("9.995e3", 2, &Policy::AwayFromZero), "1.00e4");
+/// assert_eq!(str_sround_exp("-9.995e3", 2, &Policy::TowardZero), "-9.99e3");
+/// ```
+pub fn str_sround_exp(n: &str, pr: usize, policy: &Policy) -> String {
+    match BigDecimal::from_str(n) {
+        // the value is already rounded, so the formatting doesn't round it again
+        Ok(value) => format!("{:.pr$e}", value.round_significant(pr + 1, policy)),
+        Err(_) => n.to_string()
+    }
+}
+
+//==============================================================================
+// Correct rounding
+//------------------------------------------------------------------------------
+
+/// Rounding of a floating-point value to a number of fractional digits, or at a signed digit
+/// position with [Round::round_at]. The exact decimal value
+/// is rounded under a [Policy] with [BigDecimal::round], and the result is the floating-point
+/// value nearest to the rounded decimal value. The non-finite values are returned unchanged.
+///
+/// ```
+/// use rounding::{Policy, Round};
+///
+/// // 1.005 is stored as 1.00499999999999989...
+/// assert_eq!(1.005_f64.round_digit(2), 1.0);
+/// assert_eq!(2.5_f64.round_digit_with(0, &Policy::ToEven), 2.0);
+/// assert_eq!((-1.21_f32).floor_digit(1), -1.3);
+/// assert_eq!(0.29_f64.ceil_digit(1), 0.3);
+/// assert_eq!(12345.6_f64.round_at(-2, &Policy::ToEven), 12300.0);
+/// ```
+pub trait Round {
+    /// Rounds to the nearest value with `pr` fractional digits, half away from zero.
+    fn round_digit(self, pr: usize) -> Self;
+    /// Truncates to `pr` fractional digits.
+    fn trunc_digit(self, pr: usize) -> Self;
+    /// Rounds toward -inf to `pr` fractional digits.
+    fn floor_digit(self, pr: usize) -> Self;
+    /// Rounds toward +inf to `pr` fractional digits.
+    fn ceil_digit(self, pr: usize) -> Self;
+    /// Rounds to `pr` fractional digits under `policy`.
+    fn round_digit_with(self, pr: usize, policy: &Policy) -> Self;
+    /// Rounds at a signed digit `position` under `policy`: to `position` fractional digits, or to
+    /// a multiple of `10^-position` if it's negative.
+    fn round_at(self, position: i32, policy: &Policy) -> Self;
+}
+
+impl<T: Float> Round for T {
+    #[inline]
+    fn round_digit(self, pr: usize) -> T {
+        self.round_digit_with(pr, &Policy::AwayFromZero)
+    }
+
+    #[inline]
+    fn trunc_digit(self, pr: usize) -> T {
+        self.round_digit_with(pr, &Policy::TowardZero)
+    }
+
+    #[inline]
+    fn floor_digit(self, pr: usize) -> T {
+        self.round_digit_with(pr, &Policy::Floor)
+    }
+
+    #[inline]
+    fn ceil_digit(self, pr: usize) -> T {
+        self.round_digit_with(pr, &Policy::Ceiling)
+    }
+
+    #[inline]
+    fn round_digit_with(self, pr: usize, policy: &Policy) -> T {
+        self.round_at(i32::try_from(pr).unwrap_or(i32::MAX), policy)
+    }
+
+    fn round_at(self, position: i32, policy: &Policy) -> T {
+        // beyond these positions, the result is the value itself, or 0 or an infinity, so there's
+        // no need to write the huge numbers of the farther ones
+        let position = position.clamp(-T::EXP_BIAS - 2, T::EXP_BIAS + T::MANTISSA_BITS as i32);
+        match BigDecimal::from_float(self) {
+            // the parsing gives the nearest value, or an infinity if the rounding overflows
+            Some(exact) => T::from_str(&exact.round_at(position, policy).to_string()).unwrap_or(self),
+            None => self
+        }
+    }
+}
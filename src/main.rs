@@ -1,321 +1,535 @@
-// Detects rounding discrepancies in the f64 implementation of Display::fmt "{:.prec$}",
+// Detects rounding discrepancies in the f64 and f32 implementations of Display::fmt "{:.prec$}",
 // for a range of floating-point values.
 //
-// Usage: rounding [-v][-n] [depth]
+// Usage: rounding [-v][-n][-g generator][-i ints][-s scales][-d pattern][-a][-e][-p policy][-m notation][-f32][-f64][-x][-r] [depth]
 //
 // depth : max number of digits in the fractional part in the test
 // -v : verbose output
 // -n : negative values
+// -g generator : generator of the values, among ties (default), carry (values like 9.995 or
+//                 -999.9995 whose rounding propagates a carry through all their digits), exact
+//                 (binary values which are exact decimal ties, like 0.125 or 2.5) and bits
+//                 (all the bit patterns of the floating-point type, at all the precisions; f64 requires --sample)
+// -i ints : integer parts of the values (default: 0), as a comma-separated list of values n,
+//           ranges a..b or a..=b, and magnitude sweeps 10^a..=10^b (10^k - 1 and 10^k, for k = a..=b)
+// -s scales : scales the values by 10^k for k in a range a..=b, like -300..=300, and reduces the
+//             precision by k to keep the same tie digit; the scales above the greatest precision of
+//             the values give negative precisions, so they are skipped; limited to -400..=400
+// -d pattern : endings of the values (default: 5), as a comma-separated list of digit strings,
+//              where d{n} repeats the digit d n times, like 4,5,6 or 49{5},5,50{4}1
+// -a, -e : rounding policy of the string-based rounding (away from zero, to even)
+// -p policy : rounding policy of the string-based rounding, among
+//             even, away, zero, floor, ceiling, half-zero, half-down, half-up, half-odd, 05up
+// -m notation : notation of the rounded values, among fixed (default, "{:.pr$}") and sci
+//               (scientific, "{:.n$e}", compared with the rounding to n + 1 significant digits,
+//               with the exponent adjusted after a carry)
+// -f32, -f64 : tested floating-point types (default: f64), reported separately
+// -x : exhaustive verification of all the finite f32 values, for precisions 0 to depth
+// -r : compares the naive implementations of Round::round_digit and trunc_digit, which multiply by a
+//      power of 10, with the correct ones, for the values of the generator
+// --sample n : tests n values drawn at random among those of the generator
+// --seed s : seed of the random sample (default: from the clock), to reproduce a run; requires --sample
+// -t threads : number of threads (default: number of available cores)
+// -c file : saves a checkpoint of the scan periodically in a file, for a single tested type
+// --resume file : resumes the scan saved in a checkpoint file, with its parameters, and keeps
+//                 saving the checkpoints in that file unless another one is given with -c
+// --format fmt : output format, among text (default), json (JSON Lines) and csv; json and csv
+//                output a record for each tested value and a summary record
+// --report fmt file : writes a test report of the scans in a file, in the format junit (JUnit XML)
+//                     or tap (Test Anything Protocol); the Display bugs are failures
+// --baseline file : compares the discrepancies with those of a baseline file, and lists the
+//                   new and disappeared ones
+// --save-baseline file : saves the discrepancies in a baseline file
+// --max-bugs n : exits with code 2 if a tested type has more than n Display bugs
+// --max-bug-rate p : exits with code 2 if a tested type has more than p % of Display bugs
+//
+// Usage: rounding explain [-f32][-f64][-a][-e][-p policy] value precision
+//
+// Explains the rounding of a single decimal value to a precision by Display::fmt: bits, exact value,
+// neighbours, distance to the tie, results of the roundings under each policy, and verdict
 
 use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::exit;
 use std::str::FromStr;
-use std::time::Instant;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use rounding::{parse_scales, IntegerParts, Policy, TiePattern};
+use rounding::baseline::{write_diff, Baseline};
+use rounding::checkpoint::Checkpoint;
+use rounding::explain::explain;
+use rounding::report::{write_report, ReportFormat, Suite, Thresholds};
+use rounding::scan::{compare_round, find_issues_into, verify_f32_range, Format, Generator, Notation, Sample, ScanOptions};
+
+/// Exit code when a threshold of --max-bugs or --max-bug-rate is exceeded.
+const EXIT_THRESHOLD: i32 = 2;
 
-mod tests;
+const USAGE: &str = "Usage: rounding [-v][-n][-g generator][-i ints][-s scales][-d pattern][-a][-e][-p policy][-m notation][-f32][-f64][-x][-r][--sample n][--seed s][-t threads][-c file][--resume file][--format fmt][--report fmt file][--baseline file][--save-baseline file][--max-bugs n][--max-bug-rate p][depth = 1..15]";
+
+const EXPLAIN_USAGE: &str = "Usage: rounding explain [-f32][-f64][-a][-e][-p policy] value precision";
 
 fn main() {
-    let mut depth = 6;
-    let mut verbose = false;
-    let mut negative = false;
-    let mut policy = Policy::ToEven;
+    if env::args().nth(1).as_deref() == Some("explain") {
+        explain_value(env::args().skip(2));
+        return;
+    }
+    let mut options = ScanOptions {
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        ..ScanOptions::default()
+    };
+    let mut floats = Vec::new();
+    let mut exhaustive = false;
+    let mut compare = false;
+    let mut resume = None;
+    let mut report = None;
+    let mut old_baseline = None;
+    let mut save_baseline = None;
+    let mut max_bugs = None;
+    let mut sample = None;
+    let mut seed = None;
+    let mut max_rate = None;
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
         match arg {
             opt if opt.starts_with('-') => {
                 match opt.as_ref() {
-                    "-e" => policy = Policy::ToEven,
-                    "-a" => policy = Policy::AwayFromZero,
-                    "-v" => verbose = true,
-                    "-n" => negative = true,
+                    "-s" => {
+                        match args.next().map(|scales| parse_scales(&scales)) {
+                            Some(Ok(scales)) => options.scales = scales,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-d" => {
+                        match args.next().map(|pattern| TiePattern::from_str(&pattern)) {
+                            Some(Ok(pattern)) => options.pattern = pattern,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-e" => options.policy = Policy::ToEven,
+                    "-a" => options.policy = Policy::AwayFromZero,
+                    "-p" => {
+                        match args.next().map(|name| Policy::from_str(&name)) {
+                            Some(Ok(p)) => options.policy = p,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-m" => {
+                        match args.next().map(|name| Notation::from_str(&name)) {
+                            Some(Ok(n)) => options.notation = n,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-v" => options.verbose = true,
+                    "-n" => options.negative = true,
+                    "-g" => {
+                        match args.next().map(|name| Generator::from_str(&name)) {
+                            Some(Ok(g)) => options.generator = g,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-i" => {
+                        match args.next().map(|ints| IntegerParts::from_str(&ints)) {
+                            Some(Ok(ints)) => options.integers = ints,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-f32" if !floats.contains(&"f32") => floats.push("f32"),
+                    "-f64" if !floats.contains(&"f64") => floats.push("f64"),
+                    "-f32" | "-f64" => {}
+                    "-x" => exhaustive = true,
+                    "-r" => compare = true,
+                    "--sample" => {
+                        match args.next().map(|n| u64::from_str(&n)) {
+                            Some(Ok(n)) => sample = Some(n),
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--seed" => {
+                        match args.next().map(|s| u64::from_str(&s)) {
+                            Some(Ok(s)) => seed = Some(s),
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-t" => {
+                        match args.next().map(|n| usize::from_str(&n)) {
+                            Some(Ok(n)) if n > 0 => options.threads = n,
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "-c" => {
+                        match args.next() {
+                            Some(file) => options.checkpoint = Some(PathBuf::from(file)),
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--resume" => {
+                        match args.next().map(|file| (Checkpoint::load(&file), file)) {
+                            Some((Ok(checkpoint), file)) => resume = Some((checkpoint, PathBuf::from(file))),
+                            Some((Err(e), file)) => {
+                                println!("cannot read checkpoint '{file}': {e}");
+                                exit(1);
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--format" => {
+                        match args.next().map(|name| Format::from_str(&name)) {
+                            Some(Ok(f)) => options.format = f,
+                            Some(Err(e)) => {
+                                println!("{e}");
+                                return;
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--report" => {
+                        match (args.next().map(|name| ReportFormat::from_str(&name)), args.next()) {
+                            (Some(Ok(f)), Some(file)) => report = Some((f, PathBuf::from(file))),
+                            (Some(Err(e)), _) => {
+                                println!("{e}");
+                                return;
+                            }
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--baseline" => {
+                        match args.next().map(|file| (Baseline::load(&file), file)) {
+                            Some((Ok(baseline), _)) => old_baseline = Some(baseline),
+                            Some((Err(e), file)) => {
+                                println!("cannot read baseline '{file}': {e}");
+                                exit(1);
+                            }
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--save-baseline" => {
+                        match args.next() {
+                            Some(file) => save_baseline = Some(PathBuf::from(file)),
+                            None => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--max-bugs" => {
+                        match args.next().map(|n| u64::from_str(&n)) {
+                            Some(Ok(n)) => max_bugs = Some(n),
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
+                    "--max-bug-rate" => {
+                        match args.next().map(|p| f64::from_str(&p)) {
+                            Some(Ok(p)) if p >= 0.0 => max_rate = Some(p),
+                            _ => {
+                                println!("{USAGE}");
+                                return;
+                            }
+                        }
+                    }
                     _ => println!("unknown -option '{opt}'")
                 }
             }
             arg => {
                 match usize::from_str(&arg) {
                     Ok(num) if 0 < num && num < 15 => {
-                        depth = num;
+                        options.depth = num;
                     }
                     _ => {
-                        println!("Usage: rounding [-v][-n][-a][-e][depth = 1..15]");
+                        println!("{USAGE}");
                         return;
                     }
                 }
             }
         }
     }
-    let timer = Instant::now();
-    find_issues(depth, verbose, negative, &policy);
-    let elapsed = timer.elapsed();
-    println!("elapsed time: {:.3} s", elapsed.as_secs_f64());
-}
-
-
-/// Iterates through floating-point values and compares Display::fmt implementation for f64
-/// and simple string-based rounding to detect discrepancies.
-///
-/// * `depth`: maximum number of fractional digits to test
-/// * `verbose`: displays all values
-/// * `negative`: tests negative values instead of positive ones
-///
-/// Note: we could also check [Round::round_digit] for comparison but it's not correct all
-/// the time anyway.
-fn find_issues(depth: usize, verbose: bool, negative: bool, policy: &Policy) {
-    let it = RoundTestIter::new(depth, negative);
-    let mut nbr_test = 0;
-    let mut nbr_error = 0;
-    if verbose {
-        println!("'original value' :'precision': 'Display-rounded' <> 'expected'")
+    if exhaustive {
+        let timer = Instant::now();
+        verify_all_f32(options.depth, options.verbose, options.threads);
+        let elapsed = timer.elapsed();
+        println!("elapsed time: {:.3} s", elapsed.as_secs_f64());
+        return;
     }
-    for (sval, pr) in it {
-        let val = f64::from_str(&sval).expect(&format!("error converting {} to f64", sval));
-        let display_val = format!("{val:.pr$}");
-        let sround_val = str_sround(&sval, pr, policy);
-        let comp = if display_val == sround_val {
-            "=="
-        } else {
-            nbr_error += 1;
-            "<>"
-        };
-        nbr_test += 1;
-        if verbose {
-            println!("{sval:<8}:{pr}: {display_val} {comp} {sround_val}");
+    if let (Some(skipped), None) = (options.skipped_scales(), &resume) {
+        // the tie digit of these scaled values would be in the integer part
+        if skipped == options.scales {
+            println!("no value can be tested with the scales {}..={} at depth {}: the greatest scale is {}",
+                     options.scales.start(), options.scales.end(), options.depth, options.max_precision());
+            exit(1);
         }
+        eprintln!("the scales {}..={} are above the greatest precision {} of the values, so they are skipped",
+                  skipped.start(), skipped.end(), options.max_precision());
     }
-    println!("\n=> {nbr_error} / {nbr_test} error(s) for depth 0-{depth}, so {} %",
-             f64_sround(100.0 * nbr_error as f64 / nbr_test as f64, 1, &Policy::AwayFromZero));
-}
-
-//==============================================================================
-// Iteration through floating-point values (string representation)
-//------------------------------------------------------------------------------
-
-const INIT_STEP: u8 = b'a';
-const LAST_STEP: u8 = b'9';
-
-// const INIT_DIGIT: u8 = b'4';     // to test 0.*4 and 0.*5 values
-const INIT_DIGIT: u8 = b'5';        // to test 0.*5 values only
-
-struct RoundTestIter {
-    base: Vec<u8>,
-    precision: usize,
-    max: usize
-}
-
-impl RoundTestIter {
-    pub fn new(max: usize, negative: bool) -> RoundTestIter {
-        RoundTestIter {
-            base: if negative { b"-0.a".to_vec() } else { b"0.a".to_vec() },
-            precision: 1,
-            max,
+    let collect = old_baseline.is_some() || save_baseline.is_some();
+    if collect && resume.is_some() {
+        println!("a baseline can't be made from a resumed scan");
+        exit(1);
+    }
+    if seed.is_some() && sample.is_none() {
+        println!("--seed only applies to a random sample, given with --sample");
+        exit(1);
+    }
+    // 2^64 bit patterns can't be scanned
+    let f64_tested = floats.is_empty() || floats.contains(&"f64");
+    if options.generator == Generator::Bits && f64_tested && sample.is_none() && resume.is_none() {
+        println!("the bit patterns of f64 can only be tested in a random sample, given with --sample");
+        exit(1);
+    }
+    // only the records are written to stdout in the machine-readable formats
+    let text = options.format == Format::Text;
+    if let Some(count) = sample {
+        let seed = seed.unwrap_or_else(|| {
+            let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_nanos() as u64);
+            if !text {
+                eprintln!("random seed: {seed}");
+            }
+            seed
+        });
+        options.sample = Some(Sample { count, seed });
+    }
+    if compare {
+        if floats.is_empty() {
+            floats.push("f64");
+        }
+        for float in floats {
+            let timer = Instant::now();
+            let mut out = std::io::stdout().lock();
+            let result = match float {
+                "f32" => compare_round::<f32, _>(&mut out, &options),
+                _ => compare_round::<f64, _>(&mut out, &options),
+            };
+            // a failure to write to stdout panics in println!, too
+            result.expect("failed printing to stdout");
+            println!("elapsed time: {:.3} s", timer.elapsed().as_secs_f64());
         }
+        return;
     }
-}
-
-/// step[pr]:
-/// 'a' : checks base + 4*10^-pr, then jumps to 'b'
-/// 'b' : checks base + 5*10^-pr, then tries pr+1, otherwise increases base digits and jumps to 'a'
-/// '0'-'9': base digits
-impl Iterator for RoundTestIter {
-    type Item = (String, usize);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.base.pop() {
-            Some(step) if step >= b'a' => {
-                let mut value = self.base.clone();
-                value.push(step as u8 - INIT_STEP + INIT_DIGIT);
-                // 'value' only contains ASCII characters:
-                let result = Some((unsafe { String::from_utf8_unchecked(value) }, self.precision - 1));
-                if step as u8 - INIT_STEP + INIT_DIGIT == b'5' {
-                    if self.precision < self.max {
-                        self.base.push(b'0');
-                        self.base.push(INIT_STEP);
-                        self.precision += 1;
-                    } else {
-                        self.precision -= 1;
-                        loop {
-                            match self.base.pop() {
-                                Some(digit) if digit == LAST_STEP => {
-                                    self.precision -= 1;
-                                }
-                                Some(digit) if digit != b'.' => {
-                                    self.base.push(1 + digit as u8);
-                                    self.base.push(INIT_STEP);
-                                    self.precision += 1;
-                                    break;
-                                }
-                                _ => break
-                            }
-                        }
-                    }
-                    result
-                } else {
-                    self.base.push(step + 1);
-                    result
-                }
+    // a checkpoint holds the state of a single scan
+    if options.checkpoint.is_some() && floats.len() > 1 {
+        println!("a checkpoint can be saved for a single tested type, not for {}", floats.join(" and "));
+        exit(1);
+    }
+    if let Some((checkpoint, file)) = &resume {
+        if !floats.is_empty() && floats != [checkpoint.float.as_str()] {
+            println!("the checkpoint '{}' is a scan of {}, not of {}", file.display(), checkpoint.float, floats.join(" and "));
+            exit(1);
+        }
+        if text {
+            println!("resuming {} scan at value {} / depth {}", checkpoint.float, checkpoint.next, checkpoint.depth);
+        }
+        floats = vec![checkpoint.float.as_str()];
+        options.checkpoint.get_or_insert(file.clone());
+    }
+    if floats.is_empty() {
+        floats.push("f64");
+    }
+    if let Some(header) = options.format.header() {
+        println!("{header}");
+    }
+    let resume = resume.as_ref().map(|(checkpoint, _)| checkpoint);
+    let mut suites = Vec::new();
+    let mut baseline = Baseline::default();
+    for float in floats {
+        let timer = Instant::now();
+        let result = match float {
+            "f32" => find_issues_into::<f32>(&options, resume, collect.then_some(&mut baseline)),
+            _ => find_issues_into::<f64>(&options, resume, collect.then_some(&mut baseline)),
+        };
+        match result {
+            Ok(summary) => suites.push(Suite {
+                float: float.to_string(),
+                depth: resume.map_or(options.depth, |checkpoint| checkpoint.depth),
+                summary
+            }),
+            Err(e) => {
+                println!("cannot write the checkpoint or the output: {e}");
+                exit(1);
             }
-            _ => None
+        }
+        let elapsed = timer.elapsed();
+        if text {
+            println!("elapsed time: {:.3} s", elapsed.as_secs_f64());
         }
     }
-}
-
-//==============================================================================
-// Simple and naive rounding
-//------------------------------------------------------------------------------
-
-pub trait Round {
-    fn round_digit(self, pr: usize) -> Self;
-    fn trunc_digit(self, pr: usize) -> Self;
-}
-
-impl Round for f64 {
-    #[inline]
-    fn round_digit(self, pr: usize) -> f64 {
-        let n = pow10(pr as i32);
-        (self * n).round() / n
+    if let Some(mut old_baseline) = old_baseline {
+        // the types which weren't scanned are ignored
+        old_baseline.entries.retain(|d| suites.iter().any(|suite| suite.float == d.float));
+        let (appeared, disappeared) = baseline.diff(&old_baseline);
+        if text {
+            println!();
+        }
+        // a failure to write to stdout panics in println!, too
+        write_diff(&mut std::io::stdout().lock(), options.format, &appeared, &disappeared).expect("failed printing to stdout");
     }
-
-    #[inline]
-    fn trunc_digit(self, pr: usize) -> f64 {
-        let n = pow10(pr as i32);
-        (self * n).trunc() / n
+    if let Some(file) = save_baseline {
+        if let Err(e) = baseline.save(&file) {
+            println!("cannot write baseline '{}': {e}", file.display());
+            exit(1);
+        }
     }
-}
-
-fn pow10(n: i32) -> f64 {
-    match n {
-        0 => 1.0,
-        1 => 10.0,
-        2 => 100.0,
-        3 => 1000.0,
-        4 => 10000.0,
-        5 => 100000.0,
-        6 => 1000000.0,
-        7 => 10000000.0,
-        8 => 100000000.0,
-        9 => 1000000000.0,
-        10 => 10000000000.0,
-        11 => 100000000000.0,
-        n => 10.0_f64.powi(n)
+    if let Some((format, file)) = report {
+        let result = File::create(&file).and_then(|f| {
+            let mut out = BufWriter::new(f);
+            write_report(&mut out, format, &suites)?;
+            out.flush()
+        });
+        if let Err(e) = result {
+            println!("cannot write report '{}': {e}", file.display());
+            exit(1);
+        }
     }
-}
-
-//==============================================================================
-// String-based rounding (for comparison)
-//------------------------------------------------------------------------------
-
-#[derive(Debug)]
-pub enum Policy {
-    ToEven,
-    AwayFromZero
-}
-
-/// Rounds the fractional part of `n` to `pr` digits, using [str_sround] to perform
-/// a rounding to the nearest, away from zero.
-///
-/// * `n`: floating-point value to round
-/// * `pr`: number of digits to keep in the fractional part
-///
-/// ```
-/// assert_eq!(f64_sround(2.95, 1), "3.0");
-/// assert_eq!(f64_sround(-2.95, 1), "-3.0");
-/// ```
-pub fn f64_sround(n: f64, pr: usize, policy: &Policy) -> String {
-    let s = n.to_string();
-    if !n.is_normal() {
-        s
-    } else {
-        str_sround(&s, pr, policy)
+    let exceeded = Thresholds { max_bugs, max_rate }.exceeded(&suites);
+    for message in &exceeded {
+        eprintln!("{message}");
+    }
+    if !exceeded.is_empty() {
+        exit(EXIT_THRESHOLD);
     }
 }
 
-/// Rounds the fractional part of `n` to `pr` digits, using `str_sround()` to perform
-/// a rounding to the nearest (on the absolute value). The rounding is made by processing the
-/// string, using the "away from zero" method.
-///
-/// * `n`: string representation of the floating-point value to round. It must contain more than
-/// `pr` digits in the fractional part and ideally the last non-null digit must be rounded properly
-/// (by default of anything better, a `format!("{:.}", f)` of the value - see [f64_sround])
-/// * `pr`: number of digits to keep in the fractional part
-///
-/// ```
-/// assert_eq!(f64_sround("2.95", 1, Policy::ToEven), "3.0");
-/// assert_eq!(f64_sround("-2.95", 1, Policy::ToEven), "-3.0");
-/// ```
-pub fn str_sround(n: &str, pr: usize, policy: &Policy) -> String {
-    let mut s = n.to_string().into_bytes();
-    match s.iter().position(|&x| x == b'.') {
-        None => {
-            s.push(b'.');
-            for _ in 0..pr {
-                s.push(b'0');
-            }
-            unsafe { String::from_utf8_unchecked(s) }
-        }
-        Some(mut pos) => {
-            let prec = s.len() - pos - 1;
-            if prec < pr {
-                for _ in prec..pr {
-                    s.push(b'0')
-                }
-            } else if prec > pr {
-                let ch = *s.iter().nth(pos + pr + 1).unwrap();
-                s.truncate(pos + pr + 1);
-                if ch >= b'5' {
-                    // increment s
-                    let mut frac = 0;
-                    let mut int = 0;
-                    let mut is_frac = true;
-                    loop {
-                        match s.pop() {
-                            Some(b'9') if is_frac => {
-                                frac += 1;
-                            }
-                            Some(b'.') => is_frac = false,
-                            Some(b'9') if !is_frac => {
-                                int += 1;
-                            }
-                            Some(b'-') => {
-                                s.push(b'-');
-                                s.push(b'1');
-                                break;
-                            }
-                            Some(ch2) => {
-                                match policy {
-                                    Policy::ToEven => {
-                                        if ch > b'5' || ch2 & 1 != 0 || frac != 0 || int != 0 {
-                                            s.push(ch2 + 1)
-                                        } else {
-                                            s.push(ch2)
-                                        }
-                                    }
-                                    Policy::AwayFromZero => s.push(ch2 + 1),
-                                }
-                                break;
-                            }
-                            None => {
-                                s.push(b'1');
-                                break;
-                            },
-                        }
-                    }
-                    if !is_frac {
-                        for _ in 0..int {
-                            s.push(b'0');
-                        }
-                        pos += int;
-                        s.push(b'.');
+/// Prints the explanation of the rounding of a single value, for the `explain` subcommand.
+fn explain_value(mut args: impl Iterator<Item = String>) {
+    let mut float = "f64";
+    let mut policy = Policy::ToEven;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        // negative values start with '-', too
+        match arg.as_ref() {
+            "-f32" => float = "f32",
+            "-f64" => float = "f64",
+            "-e" => policy = Policy::ToEven,
+            "-a" => policy = Policy::AwayFromZero,
+            "-p" => {
+                match args.next().map(|name| Policy::from_str(&name)) {
+                    Some(Ok(p)) => policy = p,
+                    Some(Err(e)) => {
+                        println!("{e}");
+                        return;
                     }
-                    for _ in 0..frac {
-                        s.push(b'0');
+                    None => {
+                        println!("{EXPLAIN_USAGE}");
+                        return;
                     }
                 }
             }
-            // removes '.' if no digit after:
-            if s.len() == pos + 1 {
-                s.pop();
+            _ => positional.push(arg),
+        }
+    }
+    let (value, pr) = match &positional[..] {
+        [value, pr] => match usize::from_str(pr) {
+            Ok(pr) => (value, pr),
+            Err(_) => {
+                println!("{EXPLAIN_USAGE}");
+                return;
             }
-            // 's' only contains ASCII characters:
-            unsafe { String::from_utf8_unchecked(s) }
+        },
+        _ => {
+            println!("{EXPLAIN_USAGE}");
+            return;
+        }
+    };
+    let result = match float {
+        "f32" => explain::<f32>(value, pr, &policy),
+        _ => explain::<f64>(value, pr, &policy),
+    };
+    match result {
+        Ok(text) => print!("{text}"),
+        Err(e) => {
+            println!("{e}");
+            exit(1);
+        }
+    }
+}
+
+/// Verifies `format!("{:.pr$}")` for all the finite f32 values, and for every precision `pr`
+/// from 0 to `max_pr`, against the correct rounding of the exact value, then prints the
+/// mismatches and a summary.
+///
+/// * `max_pr`: maximum number of digits in the fractional part
+/// * `verbose`: prints the progress
+/// * `threads`: number of threads
+fn verify_all_f32(max_pr: usize, verbose: bool, threads: usize) {
+    if verbose {
+        println!("verifying all f32 values with {threads} thread(s)");
+    }
+    let (tested, mismatches) = verify_f32_range(0..1 << 32, max_pr, threads, |chunk, chunks| {
+        if verbose {
+            eprintln!("chunk {chunk} / {chunks} done");
         }
+    });
+    for m in &mismatches {
+        let val = f32::from_bits(m.bits as u32);
+        println!("{val:e} (0x{:08x}):{}: {} <> {}", m.bits, m.pr, m.display, m.expected);
     }
+    println!("\n=> {} mismatch(es) for {tested} f32 value(s) at precision 0-{max_pr}", mismatches.len());
 }
@@ -0,0 +1,59 @@
+// Pseudo-random number generation, for the reproducible sampling of the values.
+
+/// SplitMix64 pseudo-random number generator: small, fast, and good enough to sample the values
+/// to test. The sequence only depends on the seed, and any of its numbers can be computed
+/// directly with [SplitMix64::at], so that the samples can be drawn by several threads.
+///
+/// ```
+/// use rounding::random::SplitMix64;
+///
+/// let mut rng = SplitMix64::new(42);
+/// let first = rng.next_u64();
+/// assert_eq!(SplitMix64::at(42, 0), first);
+/// assert_eq!(SplitMix64::at(42, 1), rng.next_u64());
+/// assert!(rng.below(10) < 10);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64
+}
+
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    /// Next number of the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        mix(self.state)
+    }
+
+    /// Next number of the sequence, reduced to the range `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        reduce(self.next_u64(), bound)
+    }
+
+    /// Number of index `n` in the sequence of `seed`.
+    pub fn at(seed: u64, n: u64) -> u64 {
+        mix(seed.wrapping_add(n.wrapping_add(1).wrapping_mul(GAMMA)))
+    }
+
+    /// Number of index `n` in the sequence of `seed`, reduced to the range `0..bound`.
+    pub fn at_below(seed: u64, n: u64, bound: u64) -> u64 {
+        reduce(SplitMix64::at(seed, n), bound)
+    }
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Maps `r` to `0..bound` with a multiplication, which is nearly uniform for a 64-bit `r`.
+fn reduce(r: u64, bound: u64) -> u64 {
+    ((r as u128 * bound as u128) >> 64) as u64
+}
@@ -0,0 +1,197 @@
+// Test reports of the scans, for the continuous integration systems.
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::str::FromStr;
+use crate::scan::Summary;
+use crate::{f64_sround, Policy};
+
+/// Format of a test report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML
+    JUnit,
+    /// Test Anything Protocol, version 13
+    Tap
+}
+
+impl ReportFormat {
+    /// All the report formats.
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::JUnit, ReportFormat::Tap];
+
+    /// Name of the format, as parsed by [ReportFormat::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportFormat::JUnit => "junit",
+            ReportFormat::Tap => "tap",
+        }
+    }
+}
+
+/// Parses the name of a report format (see [ReportFormat::name]).
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::report::ReportFormat;
+///
+/// assert_eq!(ReportFormat::from_str("tap"), Ok(ReportFormat::Tap));
+/// assert!(ReportFormat::from_str("html").is_err());
+/// ```
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ReportFormat::ALL.into_iter()
+            .find(|f| f.name() == s)
+            .ok_or_else(|| format!("unknown report format '{s}'"))
+    }
+}
+
+/// Result of the scan of a floating-point type, which is reported as a test suite.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suite {
+    /// Name of the floating-point type
+    pub float: String,
+    /// Maximum number of fractional digits
+    pub depth: usize,
+    /// Counters of the scan
+    pub summary: Summary
+}
+
+/// Test case of a suite: a class of discrepancy.
+struct Case {
+    name: &'static str,
+    count: u64,
+    /// The discrepancies make the test fail
+    failing: bool
+}
+
+impl Suite {
+    /// Test cases of the suite. The Display bugs are failures, but the decimal-intent mismatches
+    /// are the expected behaviour of Display, so they're only reported for information.
+    fn cases(&self) -> [Case; 2] {
+        [
+            Case { name: "Display bugs", count: self.summary.bugs, failing: true },
+            Case { name: "decimal-intent mismatches", count: self.summary.intents, failing: false },
+        ]
+    }
+
+    /// Description of the count of a test case.
+    fn describe(&self, case: &Case) -> String {
+        format!("{} {} / {} {} value(s) for depth 0-{}", case.count, case.name, self.summary.tests, self.float, self.depth)
+    }
+}
+
+/// Writes a test report of the `suites` in `out`. Each class of discrepancy of a suite is a
+/// test case, which fails if there is at least one Display bug.
+///
+/// * `out`: destination of the report
+/// * `format`: format of the report
+/// * `suites`: results of the scans
+///
+/// ```
+/// use rounding::report::{write_report, ReportFormat, Suite};
+/// use rounding::scan::Summary;
+///
+/// let summary = Summary { tests: 11, errors: 6, bugs: 0, intents: 6, ..Summary::default() };
+/// let mut out = Vec::new();
+/// write_report(&mut out, ReportFormat::Tap, &[Suite { float: "f64".to_string(), depth: 2, summary }]).unwrap();
+/// assert!(String::from_utf8(out).unwrap().starts_with("TAP version 13\n1..2\nok 1 - f64 Display bugs\n"));
+/// ```
+pub fn write_report<W: io::Write>(out: &mut W, format: ReportFormat, suites: &[Suite]) -> io::Result<()> {
+    match format {
+        ReportFormat::JUnit => write_junit(out, suites),
+        ReportFormat::Tap => write_tap(out, suites),
+    }
+}
+
+fn write_junit<W: io::Write>(out: &mut W, suites: &[Suite]) -> io::Result<()> {
+    let count_failures = |suite: &Suite| suite.cases().iter().filter(|c| c.failing && c.count > 0).count();
+    let failures = suites.iter().map(count_failures).sum::<usize>();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<testsuites name="rounding" tests="{}" failures="{failures}">"#, 2 * suites.len())?;
+    for suite in suites {
+        writeln!(out, r#"  <testsuite name="{}" tests="2" failures="{}">"#, suite.float, count_failures(suite))?;
+        for case in suite.cases() {
+            write!(out, r#"    <testcase classname="rounding.{}" name="{}">"#, suite.float, case.name)?;
+            if case.failing && case.count > 0 {
+                write!(out, r#"<failure message="{}"/>"#, XmlEscaped(&suite.describe(&case)))?;
+            } else {
+                write!(out, "<system-out>{}</system-out>", XmlEscaped(&suite.describe(&case)))?;
+            }
+            writeln!(out, "</testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+    writeln!(out, "</testsuites>")
+}
+
+fn write_tap<W: io::Write>(out: &mut W, suites: &[Suite]) -> io::Result<()> {
+    writeln!(out, "TAP version 13")?;
+    writeln!(out, "1..{}", 2 * suites.len())?;
+    let cases = suites.iter().flat_map(|suite| suite.cases().map(|case| (suite, case)));
+    for (number, (suite, case)) in cases.enumerate() {
+        let status = if case.failing && case.count > 0 { "not ok" } else { "ok" };
+        writeln!(out, "{status} {} - {} {}", number + 1, suite.float, case.name)?;
+        writeln!(out, "# {}", suite.describe(&case))?;
+    }
+    Ok(())
+}
+
+/// Thresholds of the Display bugs of each suite, beyond which the scans fail. Like the test
+/// reports, they ignore the decimal-intent mismatches, which are the expected behaviour of Display.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Thresholds {
+    /// Maximum number of Display bugs
+    pub max_bugs: Option<u64>,
+    /// Maximum percentage of Display bugs
+    pub max_rate: Option<f64>
+}
+
+impl Thresholds {
+    /// Descriptions of the thresholds exceeded by the `suites`, which is empty if the scans pass.
+    ///
+    /// ```
+    /// use rounding::report::{Suite, Thresholds};
+    /// use rounding::scan::Summary;
+    ///
+    /// let summary = Summary { tests: 100, errors: 52, bugs: 2, intents: 50, ..Summary::default() };
+    /// let suites = [Suite { float: "f32".to_string(), depth: 2, summary }];
+    /// assert!(Thresholds { max_bugs: Some(2), max_rate: None }.exceeded(&suites).is_empty());
+    /// assert_eq!(Thresholds { max_bugs: Some(1), max_rate: Some(1.5) }.exceeded(&suites),
+    ///            ["f32: 2 Display bug(s), more than the maximum of 1",
+    ///             "f32: 2.0 % of Display bugs, more than the maximum of 1.5 %"]);
+    /// ```
+    pub fn exceeded(&self, suites: &[Suite]) -> Vec<String> {
+        let mut exceeded = Vec::new();
+        for suite in suites {
+            let summary = &suite.summary;
+            if let Some(max) = self.max_bugs.filter(|&n| summary.bugs > n) {
+                exceeded.push(format!("{}: {} Display bug(s), more than the maximum of {max}", suite.float, summary.bugs));
+            }
+            if let Some(max) = self.max_rate.filter(|&p| summary.bug_rate() > p) {
+                exceeded.push(format!("{}: {} % of Display bugs, more than the maximum of {max} %",
+                                      suite.float, f64_sround(summary.bug_rate(), 1, &Policy::AwayFromZero)));
+            }
+        }
+        exceeded
+    }
+}
+
+/// Text with the XML special characters escaped.
+struct XmlEscaped<'a>(&'a str);
+
+impl Display for XmlEscaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '&' => f.write_str("&amp;")?,
+                '"' => f.write_str("&quot;")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
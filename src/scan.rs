@@ -0,0 +1,856 @@
+// Scans ranges of floating-point values to detect Display::fmt rounding discrepancies.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::{f64_sround, scaled, str_sround, str_sround_exp, CarryIter, ExactTieIter, Float, IntegerParts, NaiveRound, Policy, Round, RoundTestIter, TiePattern};
+use crate::baseline::{Baseline, Discrepancy};
+use crate::checkpoint::Checkpoint;
+use crate::decimal::BigDecimal;
+use crate::random::SplitMix64;
+
+/// Counters of a scan.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of tested values
+    pub tests: u64,
+    /// Number of values for which Display differs from the rounded decimal literal
+    pub errors: u64,
+    /// Number of values for which Display differs from the rounded exact value
+    pub bugs: u64,
+    /// Number of values for which Display is correct, but differs from the rounded decimal literal
+    pub intents: u64,
+    /// Number of draws of a random sample with at least one tested value
+    pub draws: u64,
+    /// Number of draws of a random sample with at least one error
+    pub failed_draws: u64
+}
+
+impl Summary {
+    pub fn add(&mut self, other: &Summary) {
+        self.tests += other.tests;
+        self.errors += other.errors;
+        self.bugs += other.bugs;
+        self.intents += other.intents;
+        self.draws += other.draws;
+        self.failed_draws += other.failed_draws;
+    }
+
+    /// 95 % confidence interval of the percentage of draws with an error, when the tested values
+    /// are a random sample (Wilson score interval). The draws are the independent trials: the values
+    /// of a draw, at several scales or precisions, are correlated.
+    ///
+    /// ```
+    /// use rounding::scan::Summary;
+    ///
+    /// let summary = Summary { tests: 30000, errors: 6000, draws: 10000, failed_draws: 5000, ..Summary::default() };
+    /// let (low, high) = summary.error_rate_interval();
+    /// assert!(49.0 < low && low < 49.1 && 50.9 < high && high < 51.0);
+    /// ```
+    pub fn error_rate_interval(&self) -> (f64, f64) {
+        const Z: f64 = 1.959964;
+        if self.draws == 0 {
+            return (0.0, 100.0);
+        }
+        let n = self.draws as f64;
+        let p = self.failed_draws as f64 / n;
+        let center = (p + Z * Z / (2.0 * n)) / (1.0 + Z * Z / n);
+        let margin = Z / (1.0 + Z * Z / n) * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt();
+        (100.0 * (center - margin).max(0.0), 100.0 * (center + margin).min(1.0))
+    }
+
+    /// Percentage of tested values with an error, or 0 if no value was tested.
+    ///
+    /// ```
+    /// use rounding::scan::Summary;
+    ///
+    /// assert_eq!(Summary { tests: 8, errors: 2, bugs: 0, intents: 2, ..Summary::default() }.error_rate(), 25.0);
+    /// ```
+    pub fn error_rate(&self) -> f64 {
+        if self.tests == 0 { 0.0 } else { 100.0 * self.errors as f64 / self.tests as f64 }
+    }
+
+    /// Percentage of tested values with a Display bug, or 0 if no value was tested.
+    ///
+    /// ```
+    /// use rounding::scan::Summary;
+    ///
+    /// assert_eq!(Summary { tests: 8, errors: 3, bugs: 1, intents: 2, ..Summary::default() }.bug_rate(), 12.5);
+    /// ```
+    pub fn bug_rate(&self) -> f64 {
+        if self.tests == 0 { 0.0 } else { 100.0 * self.bugs as f64 / self.tests as f64 }
+    }
+}
+
+/// Output format of [find_issues].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Free-form text: the discrepancies if verbose, and a summary sentence
+    #[default]
+    Text,
+    /// JSON Lines: one object per tested value, then one summary object
+    Json,
+    /// CSV: one row per tested value, then one summary row, with the columns of [Format::header]
+    Csv
+}
+
+impl Format {
+    /// All the formats.
+    pub const ALL: [Format; 3] = [Format::Text, Format::Json, Format::Csv];
+
+    /// Name of the format, as parsed by [Format::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+
+    /// Header line that must precede the records, if any. The CSV columns are shared by the
+    /// value records and the summary records, which leave the columns of the other kind empty.
+    pub fn header(&self) -> Option<&'static str> {
+        match self {
+            Format::Csv => Some("record,float,input,precision,display,expected,exact,verdict,depth,tests,errors,bugs,intents"),
+            _ => None
+        }
+    }
+}
+
+/// Parses the name of a format (see [Format::name]).
+///
+/// ```
+/// use std::str::FromStr;
+/// use rounding::scan::Format;
+///
+/// assert_eq!(Format::from_str("json"), Ok(Format::Json));
+/// assert!(Format::from_str("xml").is_err());
+/// ```
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL.into_iter()
+            .find(|f| f.name() == s)
+            .ok_or_else(|| format!("unknown output format '{s}'"))
+    }
+}
+
+/// Generator of the values tested by [find_issues].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Generator {
+    /// Values ending with the tie digit, or the endings of a pattern (see [RoundTestIter])
+    #[default]
+    Ties,
+    /// Values whose rounding propagates a carry through all their digits (see [CarryIter])
+    Carry,
+    /// Binary values which are exact decimal ties, to test the tie-breaking rule (see [ExactTieIter])
+    Exact,
+    /// Floating-point values of all the bit patterns, whose shortest representation is rounded
+    /// at all the precisions up to the depth; mostly useful with a [Sample]
+    Bits
+}
+
+impl Generator {
+    /// All the generators.
+    pub const ALL: [Generator; 4] = [Generator::Ties, Generator::Carry, Generator::Exact, Generator::Bits];
+
+    /// Name of the generator, as parsed by [Generator::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Generator::Ties => "ties",
+            Generator::Carry => "carry",
+            Generator::Exact => "exact",
+            Generator::Bits => "bits",
+        }
+    }
+}
+
+/// Parses the name of a generator (see [Generator::name]).
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Generator::ALL.into_iter()
+            .find(|g| g.name() == s)
+            .ok_or_else(|| format!("unknown generator '{s}'"))
+    }
+}
+
+/// Notation of the rounded values tested by [find_issues].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// `format!("{:.pr$}")`, rounded to `pr` fractional digits
+    #[default]
+    Fixed,
+    /// `format!("{:.n$e}")`, rounded to `n + 1` significant digits, with the exponent adjusted
+    /// after a carry; the precision `n` is chosen to round at the same digit as in fixed notation
+    Scientific
+}
+
+impl Notation {
+    /// All the notations.
+    pub const ALL: [Notation; 2] = [Notation::Fixed, Notation::Scientific];
+
+    /// Name of the notation, as parsed by [Notation::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Notation::Fixed => "fixed",
+            Notation::Scientific => "sci",
+        }
+    }
+}
+
+/// Parses the name of a notation (see [Notation::name]).
+impl FromStr for Notation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Notation::ALL.into_iter()
+            .find(|n| n.name() == s)
+            .ok_or_else(|| format!("unknown notation '{s}'"))
+    }
+}
+
+/// Random sample of the values of a generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    /// Number of values to draw
+    pub count: u64,
+    /// Seed of the pseudo-random number generator, which determines the sample
+    pub seed: u64
+}
+
+/// Options of [find_issues].
+#[derive(Clone, Debug)]
+pub struct ScanOptions {
+    /// Maximum number of fractional digits to test
+    pub depth: usize,
+    /// Displays all values
+    pub verbose: bool,
+    /// Tests negative values instead of positive ones
+    pub negative: bool,
+    /// Generator of the tested values
+    pub generator: Generator,
+    /// Integer parts of the tested values, for [Generator::Ties] and [Generator::Exact]
+    pub integers: IntegerParts,
+    /// Powers of 10 by which the values are scaled (see [RoundTestIter::scaled])
+    pub scales: RangeInclusive<i32>,
+    /// Endings of the tested values, for [Generator::Ties]
+    pub pattern: TiePattern,
+    /// Notation of the rounded values
+    pub notation: Notation,
+    /// Rounding policy of the string-based rounding
+    pub policy: Policy,
+    /// Tests a random sample of the values, instead of all of them
+    pub sample: Option<Sample>,
+    /// Number of threads
+    pub threads: usize,
+    /// File where a [Checkpoint] is written periodically, if any
+    pub checkpoint: Option<PathBuf>,
+    /// Minimum time between two checkpoints
+    pub checkpoint_interval: Duration,
+    /// Output format; the machine-readable formats output all the values, regardless of `verbose`
+    pub format: Format
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            depth: 6,
+            verbose: false,
+            negative: false,
+            generator: Generator::Ties,
+            integers: IntegerParts::default(),
+            scales: 0..=0,
+            pattern: TiePattern::default(),
+            notation: Notation::Fixed,
+            policy: Policy::ToEven,
+            sample: None,
+            threads: 1,
+            checkpoint: None,
+            checkpoint_interval: Duration::from_secs(10),
+            format: Format::Text,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Greatest precision of the values of the generator, before scaling.
+    pub fn max_precision(&self) -> usize {
+        match self.generator {
+            Generator::Bits => self.depth,
+            _ => self.depth.saturating_sub(1),
+        }
+    }
+
+    /// Scales which give a negative precision for all the values of the generator, because
+    /// they're greater than [ScanOptions::max_precision], so that no scaled value is tested.
+    ///
+    /// ```
+    /// use rounding::scan::ScanOptions;
+    ///
+    /// let options = ScanOptions { depth: 3, scales: -300..=300, ..ScanOptions::default() };
+    /// assert_eq!(options.skipped_scales(), Some(3..=300));
+    /// assert_eq!(ScanOptions { scales: -3..=2, ..options }.skipped_scales(), None);
+    /// ```
+    pub fn skipped_scales(&self) -> Option<RangeInclusive<i32>> {
+        let first = (self.max_precision() as i64 + 1).max(*self.scales.start() as i64);
+        (first <= *self.scales.end() as i64).then(|| first as i32..=*self.scales.end())
+    }
+}
+
+/// Iterates through floating-point values and compares Display::fmt implementation for the
+/// floating-point type `T` with two references, to detect discrepancies:
+/// - a simple string-based rounding of the decimal literal, which is what the user intended
+/// - a rounding of the exact decimal value of the parsed float, which is what Display should give
+///
+/// Each discrepancy is labelled as a "Display bug" if Display differs from the exact-value
+/// rounding, or as a "decimal-intent mismatch" if only the decimal literal rounding differs.
+///
+/// The values are split into chunks of consecutive indices, which are scanned by
+/// `options.threads` threads, but the output is the same as a single-threaded scan. Each value
+/// is scaled by the powers of 10 of `options.scales`, and the scaled values that `T` can't
+/// represent, because they overflow or underflow to 0, are skipped.
+///
+/// With [Notation::Scientific], `format!("{:.n$e}")` is compared with the literal rounded by
+/// [crate::str_sround_exp] and with the exact value written by [BigDecimal]'s `LowerExp`, which
+/// both give `n + 1` significant digits and adjust the exponent after a carry, like `9.995e3`
+/// which gives `1.00e4` at precision 2, away from zero. The precision `n` rounds at the same digit
+/// as the fixed notation, and the values whose leading digit is that digit, like `0.05`, are
+/// skipped.
+///
+/// If `options.sample` is set, the values are drawn at random among those of the generator, with
+/// a pseudo-random number generator, so the same seed gives the same values. The text summary
+/// then gives a confidence interval of the rate of draws with an error (see
+/// [Summary::error_rate_interval]).
+///
+/// If `options.checkpoint` is set, the progress is saved periodically in that file, and at the
+/// end of the scan. The scan can be resumed from a checkpoint with `resume`, in which case its
+/// parameters replace those of `options`.
+///
+/// With a machine-readable `options.format`, a record is written for each tested value, and a
+/// summary record at the end; the header of the format isn't written (see [Format::header]).
+///
+/// Returns the counters, or an error if the checkpoint or the output couldn't be written.
+pub fn find_issues<T: Float>(options: &ScanOptions, resume: Option<&Checkpoint>) -> io::Result<Summary> {
+    find_issues_into::<T>(options, resume, None)
+}
+
+/// Scans the values like [find_issues], and adds the discrepancies to `baseline`, if any. The
+/// discrepancies are those labelled as a Display bug or as a decimal-intent mismatch, in the
+/// order of the values.
+///
+/// If the scan is resumed, only the discrepancies of the remaining values are added.
+pub fn find_issues_into<T: Float>(options: &ScanOptions, resume: Option<&Checkpoint>, baseline: Option<&mut Baseline>) -> io::Result<Summary> {
+    write_issues::<T, _>(&mut io::stdout().lock(), options, resume, baseline)
+}
+
+/// Scans the values like [find_issues_into], but writes the output in `out` instead of stdout.
+///
+/// Returns the counters, or an error if the checkpoint or the output couldn't be written.
+///
+/// ```
+/// use rounding::scan::{write_issues, ScanOptions};
+///
+/// let mut out = Vec::new();
+/// let options = ScanOptions { depth: 2, verbose: true, ..ScanOptions::default() };
+/// let summary = write_issues::<f64, _>(&mut out, &options, None, None).unwrap();
+/// let text = String::from_utf8(out).unwrap();
+/// assert!(text.contains("0.15    :1: 0.1 <> 0.2 (decimal-intent mismatch)\n"));
+/// assert_eq!(text.lines().filter(|l| l.ends_with("(decimal-intent mismatch)")).count() as u64, summary.intents);
+/// ```
+pub fn write_issues<T: Float, W: io::Write>(out: &mut W, options: &ScanOptions, resume: Option<&Checkpoint>,
+                                            mut baseline: Option<&mut Baseline>) -> io::Result<Summary>
+{
+    let mut state = match resume {
+        Some(checkpoint) => checkpoint.clone(),
+        None => Checkpoint {
+            float: T::NAME.to_string(),
+            depth: options.depth,
+            negative: options.negative,
+            generator: options.generator,
+            integers: options.integers.clone(),
+            scales: options.scales.clone(),
+            pattern: options.pattern.clone(),
+            notation: options.notation,
+            policy: options.policy,
+            sample: options.sample,
+            next: 0,
+            summary: Summary::default(),
+        }
+    };
+    let depth = state.depth;
+    if options.verbose && options.format == Format::Text {
+        writeln!(out, "'original value' :'precision': 'Display-rounded' <> 'expected' [<> 'exact'] (label)")?;
+    }
+    let params = state.clone();
+    let sample = state.sample;
+    let positions = sample.map_or(generator_total::<T>(&params), |sample| sample.count);
+    let collect = baseline.is_some();
+    let mut last_save = Instant::now();
+    let mut result = Ok(());
+    run_ordered(chunks(&params, state.next..positions), options.threads, |range| {
+        (range.clone(), scan_range::<T>(&params, range, options.verbose, options.format, collect))
+    }, |(range, (chunk_summary, output, discrepancies))| {
+        if result.is_ok() {
+            result = out.write_all(output.as_bytes());
+        }
+        if let Some(baseline) = baseline.as_mut() {
+            baseline.entries.extend(discrepancies);
+        }
+        state.summary.add(&chunk_summary);
+        state.next = range.end;
+        if let Some(path) = &options.checkpoint {
+            if result.is_ok() && (last_save.elapsed() >= options.checkpoint_interval || state.next == positions) {
+                result = state.save(path);
+                last_save = Instant::now();
+            }
+        }
+        result.is_ok()
+    });
+    result?;
+    let summary = state.summary;
+    match options.format {
+        Format::Text => {
+            writeln!(out, "\n=> {} / {} {} error(s) for depth 0-{depth}, so {} %",
+                     summary.errors, summary.tests, T::NAME,
+                     f64_sround(summary.error_rate(), 1, &Policy::AwayFromZero))?;
+            writeln!(out, "   {} {BUG_LABEL}(s), {} {INTENT_LABEL}(es)", summary.bugs, summary.intents)?;
+            if let Some(sample) = sample {
+                let (low, high) = summary.error_rate_interval();
+                writeln!(out, "   random sample of seed {}: {} / {} draw(s) with an error, 95 % confidence interval {} - {} %",
+                         sample.seed, summary.failed_draws, summary.draws,
+                         f64_sround(low, 1, &Policy::Floor), f64_sround(high, 1, &Policy::Ceiling))?;
+            }
+        }
+        Format::Json => {
+            writeln!(out, "{{\"record\":\"summary\",\"float\":\"{}\",\"depth\":{depth},\"tests\":{},\"errors\":{},\"bugs\":{},\"intents\":{}}}",
+                     T::NAME, summary.tests, summary.errors, summary.bugs, summary.intents)?;
+        }
+        Format::Csv => {
+            writeln!(out, "summary,{},,,,,,,{depth},{},{},{},{}", T::NAME, summary.tests, summary.errors, summary.bugs, summary.intents)?;
+        }
+    }
+    Ok(summary)
+}
+
+/// Number of indices of the generator of `params` (see [generate]).
+fn generator_total<T: Float>(params: &Checkpoint) -> u64 {
+    match params.generator {
+        Generator::Ties => RoundTestIter::total_with(params.depth, &params.integers, &params.pattern),
+        Generator::Carry => CarryIter::total(params.depth),
+        Generator::Exact => ExactTieIter::total(params.depth, &params.integers),
+        // the last bit pattern of f64 is a NaN anyway
+        Generator::Bits => 1_u64.checked_shl(T::BITS).unwrap_or(u64::MAX),
+    }
+}
+
+/// Chunks of work of the `positions` of a scan with `params`, which are indices, or sample
+/// numbers if `params.sample` is set.
+fn chunks(params: &Checkpoint, positions: Range<u64>) -> impl Iterator<Item = Range<u64>> + Send {
+    // each index gives a value per scale, and per precision with Generator::Bits:
+    let mut values_per_index = (*params.scales.end() as i64 - *params.scales.start() as i64 + 1) as u64;
+    if params.generator == Generator::Bits {
+        values_per_index *= params.depth as u64 + 1;
+    }
+    let chunk_len = (CHUNK_VALUES / values_per_index).max(1);
+    let end = positions.end;
+    positions.step_by(chunk_len as usize).map(move |start| start..(start + chunk_len).min(end))
+}
+
+/// Scaled values of the `range` of positions of a scan with `params` (see [chunks]).
+fn values<T: Float>(params: &Checkpoint, range: Range<u64>) -> Box<dyn Iterator<Item = (String, usize)> + '_> {
+    let it: Box<dyn Iterator<Item = (String, usize)>> = match params.sample {
+        Some(sample) => {
+            let total = generator_total::<T>(params);
+            let it = range
+                .map(move |n| SplitMix64::at_below(sample.seed, n, total))
+                .flat_map(|index| generate::<T>(params, index..index + 1));
+            Box::new(scaled(it, params.scales.clone()))
+        }
+        None => Box::new(scaled(generate::<T>(params, range), params.scales.clone()))
+    };
+    match params.generator {
+        // a negative scale divides the tie by a power of 5, and a positive one may need more bits,
+        // so the scaled values which aren't exact in T are no longer ties
+        Generator::Exact if params.scales != (0..=0) => Box::new(it.filter(|(value, _)| is_exact::<T>(value))),
+        _ => it
+    }
+}
+
+/// Checks that the decimal literal `value` is exactly representable in `T`.
+fn is_exact<T: Float>(value: &str) -> bool {
+    let literal = BigDecimal::from_str(value).ok();
+    let exact = T::from_str(value).ok().and_then(BigDecimal::from_float);
+    literal.is_some() && literal == exact
+}
+
+/// Compares the values of the `range` of positions of a scan with `params` (see [scan_values]).
+/// The draws of a random sample are compared one by one, to count those with an error.
+fn scan_range<T: Float>(params: &Checkpoint, range: Range<u64>, verbose: bool, format: Format, collect: bool)
+    -> (Summary, String, Vec<Discrepancy>)
+{
+    let (notation, policy) = (params.notation, &params.policy);
+    if params.sample.is_none() {
+        return scan_values::<T>(values::<T>(params, range), notation, policy, verbose, format, collect);
+    }
+    let mut summary = Summary::default();
+    let mut output = String::new();
+    let mut discrepancies = Vec::new();
+    for n in range {
+        let (mut draw, draw_output, draw_discrepancies) =
+            scan_values::<T>(values::<T>(params, n..n + 1), notation, policy, verbose, format, collect);
+        if draw.tests > 0 {
+            draw.draws = 1;
+            draw.failed_draws = (draw.errors > 0) as u64;
+        }
+        summary.add(&draw);
+        output.push_str(&draw_output);
+        discrepancies.extend(draw_discrepancies);
+    }
+    (summary, output, discrepancies)
+}
+
+/// Values of the indices `range` of the generator of `params`, before scaling.
+fn generate<T: Float>(params: &Checkpoint, range: Range<u64>) -> Box<dyn Iterator<Item = (String, usize)> + '_> {
+    match params.generator {
+        Generator::Ties => Box::new(RoundTestIter::with_pattern(
+            params.depth, params.negative, params.integers.clone(), params.pattern.clone(), range)),
+        Generator::Carry => Box::new(CarryIter::with_range(params.depth, params.negative, range)),
+        Generator::Exact => Box::new(ExactTieIter::with_integers(
+            params.depth, params.negative, params.integers.clone(), T::MANTISSA_BITS + 1, range)),
+        Generator::Bits => Box::new(range.flat_map(|bits| {
+            let value = T::from_bits_u64(bits).to_string();
+            (0..=params.depth).map(move |pr| (value.clone(), pr))
+        })),
+    }
+}
+
+/// Label of a discrepancy between Display and the rounded exact value of the float.
+const BUG_LABEL: &str = "Display bug";
+/// Label of a discrepancy between Display and the rounded decimal literal only.
+const INTENT_LABEL: &str = "decimal-intent mismatch";
+
+/// Approximate number of values in a chunk of work of [find_issues].
+const CHUNK_VALUES: u64 = 1 << 16;
+
+/// Verdict of a value in the machine-readable formats: no discrepancy.
+const OK_VERDICT: &str = "ok";
+/// Verdict of a value in the machine-readable formats: [BUG_LABEL].
+const BUG_VERDICT: &str = "bug";
+/// Verdict of a value in the machine-readable formats: [INTENT_LABEL].
+const INTENT_VERDICT: &str = "intent";
+
+/// Compares the values of `it` (see [find_issues]), and returns the counters, the output,
+/// which is empty in [Format::Text] unless `verbose` is set, and the discrepancies if `collect`
+/// is set.
+fn scan_values<T: Float>(it: impl Iterator<Item = (String, usize)>, notation: Notation, policy: &Policy, verbose: bool,
+                         format: Format, collect: bool)
+    -> (Summary, String, Vec<Discrepancy>)
+{
+    let mut summary = Summary::default();
+    let mut output = String::new();
+    let mut discrepancies = Vec::new();
+    for (sval, pr) in it {
+        let val = T::from_str(&sval).unwrap_or_else(|_| panic!("error converting {} to {}", sval, T::NAME));
+        let exact = match BigDecimal::from_float(val) {
+            Some(exact) if !exact.is_zero() => exact,
+            _ => continue
+        };
+        let (pr, display_val, sround_val, exact_val) = match notation {
+            Notation::Fixed => {
+                (pr, format!("{val:.pr$}"), str_sround(&sval, pr, policy), format!("{:.pr$}", exact.round(pr, &Policy::ToEven)))
+            }
+            Notation::Scientific => {
+                // the precision n of the significand which rounds at the same digit, if any
+                let n = match BigDecimal::from_str(&sval).ok().and_then(|d| d.leading_exponent()) {
+                    Some(lead) if lead + pr as i32 >= 0 => (lead + pr as i32) as usize,
+                    _ => continue
+                };
+                (n, format!("{val:.n$e}"), str_sround_exp(&sval, n, policy), format!("{exact:.n$e}"))
+            }
+        };
+        let label = if display_val != exact_val {
+            summary.bugs += 1;
+            Some(BUG_LABEL)
+        } else if display_val != sround_val {
+            summary.intents += 1;
+            Some(INTENT_LABEL)
+        } else {
+            None
+        };
+        let comp = if display_val == sround_val {
+            "=="
+        } else {
+            summary.errors += 1;
+            "<>"
+        };
+        summary.tests += 1;
+        let verdict = match label {
+            Some(BUG_LABEL) => BUG_VERDICT,
+            Some(_) => INTENT_VERDICT,
+            None => OK_VERDICT
+        };
+        if collect && label.is_some() {
+            discrepancies.push(Discrepancy {
+                float: T::NAME.to_string(),
+                input: sval.clone(),
+                precision: pr,
+                display: display_val.clone(),
+            });
+        }
+        // writing to a String can't fail
+        let _ = match format {
+            Format::Text if verbose => match label {
+                Some(BUG_LABEL) => writeln!(output, "{sval:<8}:{pr}: {display_val} {comp} {sround_val} <> {exact_val} ({BUG_LABEL})"),
+                Some(label) => writeln!(output, "{sval:<8}:{pr}: {display_val} {comp} {sround_val} ({label})"),
+                None => writeln!(output, "{sval:<8}:{pr}: {display_val} {comp} {sround_val}"),
+            },
+            Format::Text => Ok(()),
+            // the values only contain digits, '.', '-' and 'e', so they don't need to be escaped
+            Format::Json => writeln!(output,
+                "{{\"record\":\"value\",\"float\":\"{}\",\"input\":\"{sval}\",\"precision\":{pr},\"display\":\"{display_val}\",\"expected\":\"{sround_val}\",\"exact\":\"{exact_val}\",\"verdict\":\"{verdict}\"}}",
+                T::NAME),
+            Format::Csv => writeln!(output, "value,{},{sval},{pr},{display_val},{sround_val},{exact_val},{verdict},,,,,", T::NAME),
+        };
+    }
+    (summary, output, discrepancies)
+}
+
+/// Processes the `tasks` with `work` on `threads` threads, and gives the results to `consume`
+/// in the order of the tasks, as soon as they're available. The process stops early if
+/// `consume` returns false.
+fn run_ordered<I, S, R, W, C>(tasks: I, threads: usize, work: W, mut consume: C)
+    where I: Iterator<Item = S> + Send, S: Send, R: Send, W: Fn(S) -> R + Sync, C: FnMut(R) -> bool
+{
+    let tasks = Mutex::new(tasks.enumerate());
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            let tx = tx.clone();
+            let (tasks, stop, work) = (&tasks, &stop, &work);
+            s.spawn(move || while !stop.load(Ordering::Relaxed) {
+                let task = tasks.lock().expect("a scanning thread panicked").next();
+                match task {
+                    Some((index, task)) => {
+                        if tx.send((index, work(task))).is_err() {
+                            break;
+                        }
+                    }
+                    None => break
+                }
+            });
+        }
+        drop(tx);
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, result) in rx {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next) {
+                next += 1;
+                if !consume(result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+    });
+}
+
+//==============================================================================
+// Comparison of the naive and correct Round
+//------------------------------------------------------------------------------
+
+/// Counters of [compare_round].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RoundComparison {
+    /// Number of tested values
+    pub tests: u64,
+    /// Number of values for which [NaiveRound::naive_round_digit] differs from [Round::round_digit]
+    pub round: u64,
+    /// Number of values for which [NaiveRound::naive_trunc_digit] differs from [Round::trunc_digit]
+    pub trunc: u64
+}
+
+impl RoundComparison {
+    pub fn add(&mut self, other: &RoundComparison) {
+        self.tests += other.tests;
+        self.round += other.round;
+        self.trunc += other.trunc;
+    }
+}
+
+/// Iterates through the values of the generator of `options`, like [find_issues], and counts
+/// how often the naive implementations of [NaiveRound], which multiply the value by a power of
+/// 10, differ from the correct implementations of [Round], then writes a summary in `out`. With
+/// `options.verbose`, the differences are listed.
+///
+/// The options `notation`, `policy`, `checkpoint` and `format` aren't used.
+///
+/// Returns the counters, or an error if the output couldn't be written.
+///
+/// ```
+/// use rounding::scan::{compare_round, ScanOptions};
+///
+/// let mut out = Vec::new();
+/// let comparison = compare_round::<f64, _>(&mut out, &ScanOptions { depth: 3, ..ScanOptions::default() }).unwrap();
+/// assert_eq!(comparison.tests, 111);
+/// assert!(comparison.round > 0);
+/// assert!(String::from_utf8(out).unwrap().starts_with("\n=> "));
+/// ```
+pub fn compare_round<T: Float, W: io::Write>(out: &mut W, options: &ScanOptions) -> io::Result<RoundComparison> {
+    let params = Checkpoint {
+        float: T::NAME.to_string(),
+        depth: options.depth,
+        negative: options.negative,
+        generator: options.generator,
+        integers: options.integers.clone(),
+        scales: options.scales.clone(),
+        pattern: options.pattern.clone(),
+        notation: options.notation,
+        policy: options.policy,
+        sample: options.sample,
+        next: 0,
+        summary: Summary::default(),
+    };
+    if options.verbose {
+        writeln!(out, "'original value' :'precision': 'function' 'naive' <> 'correct'")?;
+    }
+    let positions = options.sample.map_or(generator_total::<T>(&params), |sample| sample.count);
+    let mut comparison = RoundComparison::default();
+    let mut result = Ok(());
+    run_ordered(chunks(&params, 0..positions), options.threads, |range| {
+        compare_values::<T>(values::<T>(&params, range), options.verbose)
+    }, |(chunk_comparison, output)| {
+        result = out.write_all(output.as_bytes());
+        comparison.add(&chunk_comparison);
+        result.is_ok()
+    });
+    result?;
+    writeln!(out, "\n=> {} round_digit and {} trunc_digit difference(s) / {} {} value(s) for depth 0-{}",
+             comparison.round, comparison.trunc, comparison.tests, T::NAME, options.depth)?;
+    Ok(comparison)
+}
+
+/// Compares the naive and correct roundings of the values of `it` (see [compare_round]), and
+/// returns the counters and the output, which is empty unless `verbose` is set.
+fn compare_values<T: Float>(it: impl Iterator<Item = (String, usize)>, verbose: bool) -> (RoundComparison, String) {
+    let mut comparison = RoundComparison::default();
+    let mut output = String::new();
+    for (sval, pr) in it {
+        let val = T::from_str(&sval).unwrap_or_else(|_| panic!("error converting {} to {}", sval, T::NAME));
+        if !val.is_finite() || val == T::from_f64(0.0) {
+            continue;
+        }
+        comparison.tests += 1;
+        let pairs = [
+            ("round_digit", val.naive_round_digit(pr), val.round_digit(pr), &mut comparison.round),
+            ("trunc_digit", val.naive_trunc_digit(pr), val.trunc_digit(pr), &mut comparison.trunc),
+        ];
+        for (name, naive, correct, count) in pairs {
+            if naive != correct {
+                *count += 1;
+                if verbose {
+                    // writing to a String can't fail
+                    let _ = writeln!(output, "{sval:<8}:{pr}: {name} {naive} <> {correct}");
+                }
+            }
+        }
+    }
+    (comparison, output)
+}
+
+//==============================================================================
+// Exhaustive f32 verification
+//------------------------------------------------------------------------------
+
+/// Discrepancy between Display and the correct rounding of the exact value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Bit pattern of the value
+    pub bits: u64,
+    /// Number of digits in the fractional part
+    pub pr: usize,
+    /// `format!("{:.pr$}")` of the value
+    pub display: String,
+    /// Exact value rounded half to even
+    pub expected: String
+}
+
+/// Number of bit patterns verified in a chunk of work by [verify_f32_range].
+const CHUNK_SIZE: u64 = 1 << 20;
+
+/// Verifies `format!("{:.pr$}")` for all the finite f32 values of a range of bit patterns, and
+/// for every precision `pr` from 0 to `max_pr`, against the correct rounding of the exact value.
+/// The work is split in chunks shared by `threads` threads.
+///
+/// Returns the number of tested values and the mismatches, sorted by bit pattern and precision.
+///
+/// * `bits`: range of bit patterns
+/// * `max_pr`: maximum number of digits in the fractional part
+/// * `threads`: number of threads
+/// * `progress`: called with the number of each chunk once it's verified, from 1, and the number
+///   of chunks
+///
+/// ```
+/// use rounding::scan::verify_f32_range;
+///
+/// // all the values between 1.0 and 2.0:
+/// let (tested, mismatches) = verify_f32_range(0x3f80_0000..0x3f80_1000, 3, 2, |_, _| ());
+/// assert_eq!(tested, 0x1000);
+/// assert!(mismatches.is_empty());
+/// ```
+pub fn verify_f32_range<P>(bits: Range<u64>, max_pr: usize, threads: usize, progress: P) -> (u64, Vec<Mismatch>)
+    where P: Fn(u64, u64) + Sync
+{
+    let nbr_chunks = (bits.end.saturating_sub(bits.start)).div_ceil(CHUNK_SIZE);
+    let next_chunk = AtomicU64::new(0);
+    let mut tested = 0;
+    let mut mismatches = Vec::new();
+    thread::scope(|s| {
+        let progress = &progress;
+        let workers = (0..threads.max(1)).map(|_| s.spawn(|| {
+            let mut tested = 0;
+            let mut mismatches = Vec::new();
+            loop {
+                let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                if chunk >= nbr_chunks {
+                    break;
+                }
+                let start = bits.start + chunk * CHUNK_SIZE;
+                let end = (start + CHUNK_SIZE).min(bits.end);
+                for b in start..end {
+                    let val = f32::from_bits(b as u32);
+                    if let Some(exact) = BigDecimal::from_f32(val) {
+                        tested += 1;
+                        for pr in 0..=max_pr {
+                            let display = format!("{val:.pr$}");
+                            let expected = format!("{:.pr$}", exact.round(pr, &Policy::ToEven));
+                            if display != expected {
+                                mismatches.push(Mismatch { bits: b, pr, display, expected });
+                            }
+                        }
+                    }
+                }
+                progress(chunk + 1, nbr_chunks);
+            }
+            (tested, mismatches)
+        })).collect::<Vec<_>>();
+        for worker in workers {
+            let (worker_tested, mut worker_mismatches) = worker.join().expect("verification thread panicked");
+            tested += worker_tested;
+            mismatches.append(&mut worker_mismatches);
+        }
+    });
+    mismatches.sort_by_key(|m| (m.bits, m.pr));
+    (tested, mismatches)
+}
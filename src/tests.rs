@@ -1,6 +1,18 @@
 #![cfg(test)]
+// test values are deliberately given with more digits than an f64 can hold
+#![allow(clippy::excessive_precision)]
 
-use crate::{f64_sround, INIT_DIGIT, Policy, RoundTestIter, str_sround};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use crate::{f32_sround, f32_sround_sig, f64_sround, f64_sround_sig, parse_scales, CarryIter, ExactTieIter, Float, IntegerParts, NaiveRound, Policy, Round, RoundTestIter, str_sround, str_sround_at, str_sround_exp, str_sround_sig, TiePattern, MAX_SCALE};
+use crate::decimal::BigDecimal;
+use crate::baseline::Baseline;
+use crate::checkpoint::Checkpoint;
+use crate::explain::explain;
+use crate::report::{write_report, ReportFormat, Suite, Thresholds};
+use crate::random::SplitMix64;
+use crate::scan::{compare_round, find_issues, find_issues_into, write_issues, verify_f32_range, Format, Generator, Notation, Sample, ScanOptions, Summary};
 
 #[test]
 fn test_format() {
@@ -40,6 +52,69 @@ fn test_format() {
     }
 }
 
+#[test]
+fn test_format_f32() {
+    let tests = vec![
+        (0.0_f32, "0"),
+        (f32::NAN, "NaN"),
+        (1.25, "1.25"),
+        (1.125, "1.13"),
+        (29.994999, "29.99"),
+        (99.999, "100.00"),
+        (16777216.0, "16777216.00"),
+        (-0.015, "-0.02"),
+        (-9.995, "-10.00"),
+    ];
+    for (val, expected) in tests {
+        let actual = f32_sround(val, 2, &Policy::AwayFromZero);
+        assert_eq!(actual, expected, "original value: {}", val);
+    }
+    assert_eq!(1.26_f32.round_digit(1), 1.3);
+    assert_eq!(1.26_f32.trunc_digit(1), 1.2);
+    assert_eq!(1.26_f64.round_digit(1), 1.3);
+    assert_eq!((-1.26_f64).trunc_digit(1), -1.2);
+}
+
+#[test]
+fn test_round() {
+    let tests = [
+        (1.005_f64, 2, [1.0, 1.0, 1.0, 1.01]),
+        (-1.005, 2, [-1.0, -1.0, -1.01, -1.0]),
+        (2.675, 2, [2.67, 2.67, 2.67, 2.68]),
+        (0.125, 2, [0.13, 0.12, 0.12, 0.13]),
+        (-0.125, 2, [-0.13, -0.12, -0.13, -0.12]),
+        (1e300, 3, [1e300, 1e300, 1e300, 1e300]),
+        (4.35, 1, [4.3, 4.3, 4.3, 4.4]),
+        (123.456, 0, [123.0, 123.0, 123.0, 124.0]),
+    ];
+    for (val, pr, [round, trunc, floor, ceil]) in tests {
+        assert_eq!(val.round_digit(pr), round, "round_digit({val}, {pr})");
+        assert_eq!(val.trunc_digit(pr), trunc, "trunc_digit({val}, {pr})");
+        assert_eq!(val.floor_digit(pr), floor, "floor_digit({val}, {pr})");
+        assert_eq!(val.ceil_digit(pr), ceil, "ceil_digit({val}, {pr})");
+    }
+    // the naive version is off for these values:
+    assert_eq!(0.15_f64.naive_round_digit(1), 0.2);
+    assert_eq!(4.35_f64.naive_round_digit(1), 4.4);
+    for policy in Policy::ALL {
+        for val in [0.15_f32, -2.5, 16777215.0, 1e-30] {
+            let expected = f32::from_str(&format!("{:.3}", BigDecimal::from_f32(val).unwrap().round(3, &policy))).unwrap();
+            assert_eq!(val.round_digit_with(3, &policy), expected, "{val} {}", policy.name());
+        }
+    }
+    assert!(f64::NAN.round_digit(2).is_nan());
+    assert_eq!(f32::INFINITY.trunc_digit(2), f32::INFINITY);
+    let options = ScanOptions { depth: 4, threads: 3, verbose: true, ..ScanOptions::default() };
+    let (mut output, mut single) = (Vec::new(), Vec::new());
+    let comparison = compare_round::<f64, _>(&mut output, &options).unwrap();
+    assert_eq!(comparison, compare_round::<f64, _>(&mut single, &ScanOptions { threads: 1, ..options.clone() }).unwrap());
+    assert!(output == single);
+    assert_eq!(String::from_utf8(output).unwrap().lines().count() as u64, 1 + comparison.round + comparison.trunc + 2);
+    assert_eq!(comparison.tests, 1111);
+    assert!(comparison.round > 0);
+    assert_eq!(comparison.trunc, 0);
+}
+
 #[test]
 fn test_precision() {
     let f = "1.49495";
@@ -50,6 +125,704 @@ fn test_precision() {
     }
 }
 
+#[test]
+fn test_significant() {
+    let tests = [
+        ("0.00012345", 3, "0.000123", "0.000124"),
+        ("123456", 3, "123000", "124000"),
+        ("-123456", 3, "-123000", "-123000"),
+        ("0.125", 2, "0.12", "0.13"),
+        ("99.95", 3, "100", "100"),
+        ("9.995", 3, "10.0", "10.0"),
+        ("1.5", 4, "1.500", "1.500"),
+        ("-0.0", 3, "-0.00", "-0.00"),
+        ("1e-7", 1, "0.0000001", "0.0000001"),
+        ("15e20", 1, "2000000000000000000000", "2000000000000000000000"),
+    ];
+    for (val, digits, exp_even, exp_ceiling) in tests {
+        assert_eq!(str_sround_sig(val, digits, &Policy::ToEven), exp_even, "ToEven, original value: {val}");
+        assert_eq!(str_sround_sig(val, digits, &Policy::Ceiling), exp_ceiling, "Ceiling, original value: {val}");
+    }
+    assert_eq!(str_sround_sig("NaN", 2, &Policy::ToEven), "NaN");
+    for policy in Policy::ALL {
+        // the significant digits at the position of the fractional digits give the same result
+        for (val, digits, pr) in [("0.0123456", 3, 4), ("-52.5", 2, 0), ("7.77777", 6, 5)] {
+            assert_eq!(str_sround_sig(val, digits, &policy), str_sround(val, pr, &policy), "{val} {}", policy.name());
+        }
+    }
+    assert_eq!(f64_sround_sig(0.1 + 0.2, 16, &Policy::ToEven), "0.3000000000000000");
+    assert_eq!(f32_sround_sig(16777216.0, 2, &Policy::ToEven), "17000000");
+    let options = ScanOptions { depth: 4, notation: Notation::Scientific, threads: 2, ..ScanOptions::default() };
+    let summary = find_issues::<f64>(&options, None).unwrap();
+    // 0.5, 0.05, 0.005 and 0.0005 can't be rounded before their leading digit in scientific notation
+    assert_eq!(summary.tests, 1111 - 4);
+    assert_eq!(summary.bugs, 0);
+    assert_eq!(summary.errors, summary.intents);
+    let summary = find_issues::<f32>(&ScanOptions { generator: Generator::Carry, ..options }, None).unwrap();
+    assert_eq!(summary.tests, 4 * 5 - 1);
+    assert_eq!(summary.bugs, 0);
+}
+
+#[test]
+fn test_scientific() {
+    let tests = [
+        ("9.995e3", 2, "1.00e4", "9.99e3"),
+        ("9995", 3, "9.995e3", "9.995e3"),
+        ("-99.95", 2, "-1.00e2", "-9.99e1"),
+        ("0.00012345", 2, "1.23e-4", "1.23e-4"),
+        ("0.125", 0, "1e-1", "1e-1"),
+        ("0.95", 0, "1e0", "9e-1"),
+        ("1", 3, "1.000e0", "1.000e0"),
+        ("0", 2, "0.00e0", "0.00e0"),
+    ];
+    for (val, pr, exp_away, exp_zero) in tests {
+        assert_eq!(str_sround_exp(val, pr, &Policy::AwayFromZero), exp_away, "AwayFromZero, original value: {val}");
+        assert_eq!(str_sround_exp(val, pr, &Policy::TowardZero), exp_zero, "TowardZero, original value: {val}");
+    }
+    for policy in Policy::ALL {
+        for val in ["99.95", "-0.0123456", "7.5e-300"] {
+            let sci = BigDecimal::from_str(&str_sround_exp(val, 2, &policy)).unwrap();
+            assert_eq!(sci, BigDecimal::from_str(&str_sround_sig(val, 3, &policy)).unwrap(), "{val} {}", policy.name());
+        }
+    }
+    // the exact oracle gives the same result as Display for the exact ties and the carries
+    for val in [9995.0_f64, 0.125, -2.5, 999.5, 1e300, 5e-324, 0.3, 123456.0] {
+        let exact = BigDecimal::from_f64(val).unwrap();
+        for pr in 0..6 {
+            assert_eq!(format!("{exact:.pr$e}"), format!("{val:.pr$e}"), "{val:e} at precision {pr}");
+        }
+    }
+    assert_eq!(format!("{:>10.1e}", BigDecimal::from_str("-0.25").unwrap()), "   -2.5e-1");
+    let options = ScanOptions { depth: 5, generator: Generator::Carry, notation: Notation::Scientific, ..ScanOptions::default() };
+    let summary = find_issues::<f64>(&options, None).unwrap();
+    assert_eq!(summary.tests, 6 * 5 - 1);
+    assert_eq!(summary.bugs, 0);
+}
+
+#[test]
+fn test_position() {
+    let tests = [
+        ("12345.6", -2, "12300", "12400"),
+        ("-12345.6", -2, "-12300", "-12300"),
+        ("99950", -2, "100000", "100000"),
+        ("250", -2, "200", "300"),
+        ("350", -2, "400", "400"),
+        ("49", -2, "0", "100"),
+        ("12", -5, "0", "100000"),
+        ("-0.5", -1, "-0", "-0"),
+        ("0.125", 2, "0.12", "0.13"),
+    ];
+    for (val, position, exp_even, exp_ceiling) in tests {
+        assert_eq!(str_sround_at(val, position, &Policy::ToEven), exp_even, "ToEven, original value: {val}");
+        assert_eq!(str_sround_at(val, position, &Policy::Ceiling), exp_ceiling, "Ceiling, original value: {val}");
+    }
+    for policy in Policy::ALL {
+        for (val, pr) in [("2.95", 1), ("-0.0125", 3), ("7", 0)] {
+            assert_eq!(str_sround_at(val, pr as i32, &policy), str_sround(val, pr, &policy), "{val} {}", policy.name());
+        }
+        for (val, position, expected) in [("0", -2, "0"), ("-0", -2, "-0"), ("0", 3, "0.000"), ("-0.0", -7, "-0")] {
+            assert_eq!(str_sround_at(val, position, &policy), expected, "{val} {}", policy.name());
+        }
+        assert_eq!(0.0_f64.round_at(-2, &policy), 0.0, "{}", policy.name());
+        // 12345.6 = 123.456 * 10^2
+        let scaled = str_sround_at("123.456", 0, &policy);
+        assert_eq!(str_sround_at("12345.6", -2, &policy), format!("{scaled}00"), "{}", policy.name());
+    }
+    assert_eq!(12345.6_f64.round_at(-2, &Policy::ToEven), 12300.0);
+    assert_eq!(99950_f32.round_at(-2, &Policy::ToEven), 100000.0);
+    assert_eq!((-1234.5_f64).round_at(-3, &Policy::Floor), -2000.0);
+    assert_eq!(1e20_f64.round_at(-25, &Policy::Ceiling), 1e25);
+    assert_eq!(f64::MAX.round_at(-308, &Policy::AwayFromZero), f64::INFINITY);
+    assert_eq!(2.675_f64.round_at(2, &Policy::HalfUp), 2.675_f64.round_digit_with(2, &Policy::HalfUp));
+    // positions far beyond the digits of the value
+    assert_eq!(str_sround_at("1.5", i32::MIN, &Policy::ToEven), "0");
+    assert_eq!(str_sround_at("-1.5", i32::MIN, &Policy::Ceiling), "-0");
+    let d = BigDecimal::from_str("1.5").unwrap();
+    assert_eq!(d.round_at(i32::MIN, &Policy::Ceiling).leading_exponent(), Some(i32::MAX - 1));
+    assert_eq!(d.round_at(i32::MAX, &Policy::Floor).to_string(), "1.5");
+    assert_eq!(d.round(usize::MAX, &Policy::Floor).to_string(), "1.5");
+    assert_eq!(BigDecimal::from_str("5e2147483645").unwrap().round_at(i32::MIN, &Policy::ToEven).to_string(), "0");
+    for policy in Policy::ALL {
+        assert_eq!(1.5_f64.round_at(i32::MAX, &policy), 1.5, "{}", policy.name());
+        assert_eq!(1.5_f32.round_at(i32::MAX, &policy), 1.5, "{}", policy.name());
+    }
+    assert_eq!(1.5_f64.round_at(i32::MIN, &Policy::ToEven), 0.0);
+    assert_eq!(1.5_f64.round_at(i32::MIN, &Policy::Ceiling), f64::INFINITY);
+    assert_eq!((-1.5_f32).round_at(i32::MIN, &Policy::Ceiling), 0.0);
+    assert_eq!(f64::MIN_POSITIVE.round_digit(usize::MAX), f64::MIN_POSITIVE);
+}
+
+#[test]
+fn test_ties() {
+    let tests = [
+        ("0.25", 1, "0.2", "0.3"),
+        ("0.35", 1, "0.4", "0.4"),
+        ("0.251", 1, "0.3", "0.3"),
+        ("0.2500000001", 1, "0.3", "0.3"),
+        ("0.95", 1, "1.0", "1.0"),
+        ("9.5", 0, "10", "10"),
+        ("49.96", 1, "50.0", "50.0"),
+        ("-99.95", 1, "-100.0", "-100.0"),
+    ];
+    for (val, pr, exp_even, exp_away) in tests {
+        assert_eq!(str_sround(val, pr, &Policy::ToEven), exp_even, "ToEven, original value: {val}");
+        assert_eq!(str_sround(val, pr, &Policy::AwayFromZero), exp_away, "AwayFromZero, original value: {val}");
+    }
+}
+
+#[test]
+fn test_policies() {
+    let values = ["2.2", "2.5", "2.7", "3.5", "-2.2", "-2.5", "-2.7", "5.2", "0.2", "-0.2"];
+    let tests = [
+        (Policy::ToEven,         ["2", "2", "3", "4", "-2", "-2", "-3", "5", "0", "-0"]),
+        (Policy::AwayFromZero,   ["2", "3", "3", "4", "-2", "-3", "-3", "5", "0", "-0"]),
+        (Policy::TowardZero,     ["2", "2", "2", "3", "-2", "-2", "-2", "5", "0", "-0"]),
+        (Policy::Floor,          ["2", "2", "2", "3", "-3", "-3", "-3", "5", "0", "-1"]),
+        (Policy::Ceiling,        ["3", "3", "3", "4", "-2", "-2", "-2", "6", "1", "-0"]),
+        (Policy::HalfTowardZero, ["2", "2", "3", "3", "-2", "-2", "-3", "5", "0", "-0"]),
+        (Policy::HalfDown,       ["2", "2", "3", "3", "-2", "-3", "-3", "5", "0", "-0"]),
+        (Policy::HalfUp,         ["2", "3", "3", "4", "-2", "-2", "-3", "5", "0", "-0"]),
+        (Policy::HalfOdd,        ["2", "3", "3", "3", "-2", "-3", "-3", "5", "0", "-0"]),
+        (Policy::ZeroFiveUp,     ["2", "2", "2", "3", "-2", "-2", "-2", "6", "1", "-1"]),
+    ];
+    assert_eq!(tests.len(), Policy::ALL.len());
+    for (policy, expected) in tests {
+        for (val, exp) in values.iter().zip(expected) {
+            assert_eq!(str_sround(val, 0, &policy), exp, "{policy:?}, original value: {val}");
+        }
+        assert_eq!(str_sround("2.50", 1, &policy), "2.5", "{policy:?}, exact value");
+        assert_eq!(Policy::from_str(policy.name()), Ok(policy));
+    }
+    assert_eq!(str_sround("9.951", 1, &Policy::Ceiling), "10.0");
+    assert_eq!(str_sround("-9.951", 1, &Policy::Floor), "-10.0");
+    assert_eq!(str_sround("-9.951", 1, &Policy::Ceiling), "-9.9");
+    assert_eq!(str_sround("0.9999", 3, &Policy::HalfTowardZero), "1.000");
+    assert_eq!(str_sround("0.0005", 3, &Policy::HalfOdd), "0.001");
+    assert_eq!(str_sround("0.0015", 3, &Policy::HalfOdd), "0.001");
+    assert_eq!(str_sround("0.9951", 2, &Policy::ZeroFiveUp), "0.99");
+    assert_eq!(str_sround("0.9051", 2, &Policy::ZeroFiveUp), "0.91");
+}
+
+#[test]
+fn test_carry() {
+    for negative in [false, true] {
+        let values = CarryIter::new(12, negative).collect::<Vec<_>>();
+        assert_eq!(values.len(), 13 * 12);
+        assert!(values.iter().any(|(s, _)| s.trim_start_matches('-') == "0.99999999995"));
+        for (value, pr) in values {
+            let (int, frac) = value.trim_start_matches('-').split_once('.').unwrap();
+            let sign = if negative { "-" } else { "" };
+            let dot = if pr > 0 { "." } else { "" };
+            let carried = format!("{sign}1{}{dot}{}", "0".repeat(if int == "0" { 0 } else { int.len() }), "0".repeat(pr));
+            let truncated = format!("{sign}{int}{dot}{}", &frac[..pr]);
+            // the last kept digit is 9, except for 0.5 at precision 0
+            let odd = pr > 0 || int != "0";
+            for policy in Policy::ALL {
+                let away = match policy {
+                    Policy::AwayFromZero => true,
+                    Policy::TowardZero | Policy::HalfTowardZero => false,
+                    Policy::Floor | Policy::HalfDown => negative,
+                    Policy::Ceiling | Policy::HalfUp => !negative,
+                    Policy::ToEven => odd,
+                    Policy::HalfOdd | Policy::ZeroFiveUp => !odd,
+                };
+                let expected = if away { &carried } else { &truncated };
+                assert_eq!(&str_sround(&value, pr, &policy), expected, "{policy:?}, original value: {value}");
+            }
+            // Display must be the correct rounding of the exact value
+            let val = value.parse::<f64>().unwrap();
+            let exact = BigDecimal::from_f64(val).unwrap();
+            assert_eq!(format!("{val:.pr$}"), format!("{:.pr$}", exact.round(pr, &Policy::ToEven)), "f64 {value}");
+            let val = value.parse::<f32>().unwrap();
+            let exact = BigDecimal::from_f32(val).unwrap();
+            assert_eq!(format!("{val:.pr$}"), format!("{:.pr$}", exact.round(pr, &Policy::ToEven)), "f32 {value}");
+        }
+    }
+    let options = ScanOptions { depth: 14, generator: Generator::Carry, negative: true, ..ScanOptions::default() };
+    let summary = find_issues::<f64>(&options, None).unwrap();
+    assert_eq!(summary.tests, 15 * 14);
+    assert_eq!(summary.bugs, 0);
+}
+
+#[test]
+fn test_exact() {
+    let tests = [
+        (0.0_f64, "0"),
+        (-0.0, "-0"),
+        (0.5, "0.5"),
+        (2.0, "2"),
+        (-1.25, "-1.25"),
+        (0.1, "0.1000000000000000055511151231257827021181583404541015625"),
+        (0.15, "0.1499999999999999944488848768742172978818416595458984375"),
+        (1e23, "99999999999999991611392"),
+        (9007199254740993.0, "9007199254740992"),
+    ];
+    for (val, expected) in tests {
+        let actual = BigDecimal::from_f64(val).unwrap().to_string();
+        assert_eq!(actual, expected, "original value: {val}");
+    }
+    // smallest subnormal, 2^-1074:
+    let min = BigDecimal::from_f64(f64::from_bits(1)).unwrap().to_string();
+    assert_eq!(min.len(), 2 + 1074);
+    assert!(min[2..].trim_start_matches('0').starts_with("4940656458412465441765687928682213723650"));
+    assert!(min.ends_with('5'));
+    assert_eq!(BigDecimal::from_f64(f64::MAX).unwrap().to_string().len(), 309);
+    assert_eq!(BigDecimal::from_f64(f64::NAN), None);
+    assert_eq!(BigDecimal::from_f32(0.15).unwrap().to_string(), "0.1500000059604644775390625");
+    assert_eq!(BigDecimal::from_f32(-16777217.0).unwrap().to_string(), "-16777216");
+    assert_eq!(BigDecimal::from_f32(f32::from_bits(1)).unwrap().to_string().len(), 2 + 149);
+}
+
+#[test]
+fn test_float() {
+    fn check<T: Float>(values: &[T]) {
+        for &val in values {
+            let (negative, m, e) = val.decompose();
+            let bits = val.to_bits_u64();
+            assert_eq!(T::from_bits_u64(bits).to_bits_u64(), bits, "{} bits of {val}", T::NAME);
+            assert_eq!(BigDecimal::from_float(val), Some(BigDecimal::from_binary(negative, m, e)), "{} value {val}", T::NAME);
+            assert!(val.next_down() < val && val < val.next_up(), "{} neighbours of {val}", T::NAME);
+            assert_eq!(val.next_up().next_down().to_bits_u64(), bits, "{} neighbours of {val}", T::NAME);
+        }
+    }
+    check(&[0.1_f64, -2.5, 1e300, -1e-310, f64::MIN_POSITIVE, f64::MAX / 2.0]);
+    check(&[0.1_f32, -2.5, 1e30, -1e-40, f32::MIN_POSITIVE, f32::MAX / 2.0]);
+    assert_eq!(0.0_f64.next_up(), f64::from_bits(1));
+    assert_eq!(1.0_f32.next_down(), 1.0 - f32::EPSILON / 2.0);
+}
+
+#[test]
+fn test_decimal() {
+    let tests = [
+        ("0", "0"),
+        ("-0.000", "-0"),
+        ("+12.50", "12.5"),
+        ("007", "7"),
+        (".5", "0.5"),
+        ("5.", "5"),
+        ("1.5e3", "1500"),
+        ("-25E-3", "-0.025"),
+    ];
+    for (s, expected) in tests {
+        assert_eq!(BigDecimal::from_str(s).unwrap().to_string(), expected, "original value: {s}");
+    }
+    for s in ["", "-", ".", "1.2.3", "1e", "0x10", "NaN", "inf", "1 "] {
+        assert!(BigDecimal::from_str(s).is_err(), "original value: {s}");
+    }
+    // the powers of 10 of the digits and their opposites must fit in an i32
+    for s in ["10e2147483647", "1e2147483647", "1e-2147483648", "0.1e-2147483647", "1e99999999999"] {
+        assert!(BigDecimal::from_str(s).is_err(), "original value: {s}");
+        assert_eq!(str_sround_sig(s, 3, &Policy::ToEven), s);
+        assert_eq!(str_sround(s, 3, &Policy::ToEven), s);
+    }
+    let d = BigDecimal::from_str("-1e2147483646").unwrap();
+    assert_eq!(d.leading_exponent(), Some(2147483646));
+    assert_eq!(format!("{d:.1e}"), "-1.0e2147483646");
+    let d = BigDecimal::from_str("12e-2147483647").unwrap();
+    assert_eq!(d.leading_exponent(), Some(-2147483646));
+    assert_eq!(format!("{d:e}"), "1.2e-2147483646");
+    assert_eq!(BigDecimal::from_str("0.0e-2147483648").unwrap().to_string(), "0");
+    let mut values = ["10", "-0.5", "0.25", "-0", "9.99", "-10", "0.250", "1e-9"]
+        .map(|s| BigDecimal::from_str(s).unwrap());
+    values.sort();
+    let sorted = values.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    assert_eq!(sorted, ["-10", "-0.5", "-0", "0.000000001", "0.25", "0.25", "9.99", "10"]);
+    let d = BigDecimal::from_str("-0.004").unwrap();
+    assert_eq!(format!("{:.2}", d), "-0.00");
+    assert_eq!(format!("{:>8.3}", d), "  -0.004");
+    assert_eq!(format!("{:.2}", BigDecimal::from_str("1234e2").unwrap()), "123400.00");
+    let differences = [
+        ("0.15", "0.1", "0.05"),
+        ("1", "0.001", "0.999"),
+        ("-2.5", "2.5", "-5"),
+        ("-2.5", "-2.5", "0"),
+        ("100", "-1e-3", "100.001"),
+        ("0", "7.25", "-7.25"),
+        ("999.5", "-0.5", "1000"),
+    ];
+    for (a, b, expected) in differences {
+        let (a, b) = (BigDecimal::from_str(a).unwrap(), BigDecimal::from_str(b).unwrap());
+        assert_eq!((&a - &b).to_string(), expected, "{a} - {b}");
+    }
+}
+
+#[test]
+fn test_explain() {
+    let text = explain::<f64>("0.15", 1, &Policy::ToEven).unwrap();
+    assert!(text.contains("bits       : 0x3fc3333333333333 = +0x13333333333333 * 2^-55\n"), "{text}");
+    assert!(text.contains("next_up    : 0.15000000000000002220446049250313080847263336181640625\n"), "{text}");
+    assert!(text.contains("= -0.2000 ulp (below the tie in magnitude)\n"), "{text}");
+    assert!(text.contains("Display    : 0.1\n"), "{text}");
+    assert!(text.contains("\nhalf-up    0.2     0.1\n"), "{text}");
+    assert!(text.contains("verdict    : decimal-intent mismatch"), "{text}");
+    let text = explain::<f64>("0.125", 2, &Policy::ToEven).unwrap();
+    assert!(text.contains("distance   : 0 = 0.0000 ulp (exactly on the tie in magnitude)\n"), "{text}");
+    assert!(text.contains("verdict    : ok"), "{text}");
+    let text = explain::<f64>("0.125", 2, &Policy::AwayFromZero).unwrap();
+    assert!(text.contains("is exactly representable in f64, but rounds to 0.13 under the policy away"), "{text}");
+    let text = explain::<f32>("-0.1", 3, &Policy::ToEven).unwrap();
+    assert!(text.contains("bits       : 0xbdcccccd = -0xcccccd * 2^-27\n"), "{text}");
+    assert!(text.contains("tie        : -0.1005\n"), "{text}");
+    // the distance in ulps is beyond the range of f64 for 0 and the subnormal values
+    let text = explain::<f64>("0", 2, &Policy::ToEven).unwrap();
+    assert!(text.contains("distance   : -0.005 = -1.012e321 ulp (below the tie in magnitude)\n"), "{text}");
+    assert!(!text.contains("inf"), "{text}");
+    let text = explain::<f64>("5e-324", 2, &Policy::ToEven).unwrap();
+    assert!(text.contains(" = -1.012e321 ulp (below the tie in magnitude)\n"), "{text}");
+    let text = explain::<f64>("1.7976931348623157e308", 0, &Policy::ToEven).unwrap();
+    assert!(text.contains("distance   : -0.5 = -2.505e-293 ulp (below the tie in magnitude)\n"), "{text}");
+    assert!(explain::<f64>("1e400", 0, &Policy::ToEven).is_err());
+    assert!(explain::<f64>("0.1.5", 0, &Policy::ToEven).is_err());
+}
+
+#[test]
+fn round_test_iter_prefix() {
+    for negative in [false, true] {
+        let all = RoundTestIter::new(4, negative).collect::<Vec<_>>();
+        let mut shards = RoundTestIter::with_prefix(1, negative, "").collect::<Vec<_>>();
+        for d in 0..10 {
+            shards.extend(RoundTestIter::with_prefix(2, negative, &d.to_string()));
+            for d2 in 0..10 {
+                shards.extend(RoundTestIter::with_prefix(4, negative, &format!("{d}{d2}")));
+            }
+        }
+        assert_eq!(shards, all, "negative = {negative}");
+    }
+    assert_eq!(RoundTestIter::new(0, false).count(), 0);
+    assert_eq!(RoundTestIter::with_prefix(2, false, "12").count(), 0);
+}
+
+#[test]
+fn round_test_iter_index() {
+    for max in 1..=4 {
+        let all = RoundTestIter::new(max, true).collect::<Vec<_>>();
+        assert_eq!(all.len() as u64, RoundTestIter::total(max));
+        assert_eq!(RoundTestIter::new(max, true).len(), all.len());
+        for (index, (value, pr)) in all.iter().enumerate() {
+            let index = index as u64;
+            assert_eq!(RoundTestIter::value_at(max, true, index).as_ref(), Some(&(value.clone(), *pr)), "max = {max}");
+            assert_eq!(RoundTestIter::index_of(max, value), Some(index), "max = {max}, value {value}");
+            let mut it = RoundTestIter::from_index(max, true, index);
+            assert_eq!(it.index(), index);
+            assert_eq!(it.next().as_ref(), Some(&(value.clone(), *pr)), "max = {max}, index {index}");
+            assert_eq!(RoundTestIter::new(max, true).nth(index as usize).as_ref(), Some(&(value.clone(), *pr)));
+        }
+        assert_eq!(RoundTestIter::value_at(max, true, all.len() as u64), None);
+        assert_eq!(RoundTestIter::from_index(max, true, all.len() as u64 + 5).next(), None);
+    }
+    let mut it = RoundTestIter::new(8, false);
+    assert_eq!(it.nth(5_000_000), RoundTestIter::value_at(8, false, 5_000_000));
+    assert_eq!(it.len(), 11111111 - 5_000_001);
+    assert_eq!(it.nth(usize::MAX), None);
+    assert_eq!(RoundTestIter::index_of(3, "0.1234"), None);
+    assert_eq!(RoundTestIter::index_of(3, "0.123"), None);
+    assert_eq!(RoundTestIter::index_of(3, "1.25"), None);
+    assert_eq!(RoundTestIter::total(crate::iter::MAX_DEPTH), 111_111_111_111_111_111);
+}
+
+#[test]
+fn round_test_iter_integers() {
+    let ints = IntegerParts::from_str("7,123,10^0..10^2,1..=3").unwrap();
+    assert_eq!(ints.iter().collect::<Vec<_>>(), [7, 123, 0, 1, 9, 10, 1, 2, 3]);
+    assert_eq!(IntegerParts::from_str(&ints.to_string()), Ok(ints.clone()));
+    let total = RoundTestIter::total_with(3, &ints, &TiePattern::default());
+    assert_eq!(total, 9 * 111);
+    let all = RoundTestIter::with_integers(3, true, ints.clone(), 0..total).collect::<Vec<_>>();
+    assert_eq!(all.len() as u64, total);
+    for (i, int) in ints.iter().enumerate() {
+        let expected = RoundTestIter::new(3, true)
+            .map(|(s, pr)| (s.replacen("-0.", &format!("-{int}."), 1), pr))
+            .collect::<Vec<_>>();
+        assert_eq!(all[i * 111..(i + 1) * 111], expected, "integer part {int}");
+    }
+    for index in [0, 110, 111, 500, total - 1] {
+        let mut it = RoundTestIter::with_integers(3, true, ints.clone(), index..total);
+        assert_eq!(it.len() as u64, total - index);
+        assert_eq!(it.next().as_ref(), Some(&all[index as usize]));
+        let mut it = RoundTestIter::with_integers(3, true, ints.clone(), 0..total);
+        assert_eq!(it.nth(index as usize).as_ref(), Some(&all[index as usize]));
+    }
+    assert_eq!(RoundTestIter::with_integers(3, false, IntegerParts::default(), 0..1000).collect::<Vec<_>>(),
+               RoundTestIter::new(3, false).collect::<Vec<_>>());
+    assert!(IntegerParts::from_str("5..5").is_err());
+    assert!(IntegerParts::from_str("10^20..=10^21").is_err());
+    assert!(IntegerParts::from_str("1,,2").is_err());
+    assert!(IntegerParts::from_str("0..=18446744073709551615").is_err());
+    assert!(IntegerParts::from_str("1,0..18446744073709551615").is_err());
+    let many = IntegerParts::from_str("0..18446744073709551615").unwrap();
+    assert_eq!(many.len(), u64::MAX);
+    assert_eq!(RoundTestIter::total_with(3, &many, &TiePattern::default()), u64::MAX);
+    assert_eq!(ExactTieIter::total(3, &many), u64::MAX);
+    let options = ScanOptions { depth: 3, integers: ints, ..ScanOptions::default() };
+    assert_eq!(find_issues::<f64>(&options, None).unwrap().tests, total);
+}
+
+#[test]
+fn round_test_iter_scaled() {
+    let values = RoundTestIter::new(3, false).scaled(-300..=300).collect::<Vec<_>>();
+    // 0.5: k = -300..=0, 0.x5: k = -300..=1, 0.xx5: k = -300..=2
+    assert_eq!(values.len(), 301 + 10 * 302 + 100 * 303);
+    for (value, pr) in &values {
+        let (base, k) = value.split_once('e').map_or((value.as_str(), 0), |(b, k)| (b, i32::from_str(k).unwrap()));
+        assert_eq!(*pr as i32, base.len() as i32 - 3 - k, "{value}");
+    }
+    assert_eq!(parse_scales("5"), Ok(5..=5));
+    assert!(parse_scales("1..1").is_err());
+    assert!(parse_scales("0..-2147483648").is_err());
+    assert!(parse_scales("-2147483648..=2147483647").is_err());
+    assert_eq!(parse_scales("-400..=400"), Ok(-MAX_SCALE..=MAX_SCALE));
+    let options = ScanOptions { depth: 2, scales: -300..=300, ..ScanOptions::default() };
+    // f64 has subnormal values down to 4.9e-324:
+    assert_eq!(find_issues::<f64>(&options, None).unwrap().tests, 301 + 10 * 302);
+    // f32 is limited to 1.4e-45..3.4e38, the other values underflow or overflow:
+    let f32_summary = find_issues::<f32>(&options, None).unwrap();
+    assert_eq!(f32_summary.tests, 507);
+    assert_eq!(f32_summary.bugs, 0);
+    assert_eq!(options.skipped_scales(), Some(2..=300));
+    assert_eq!(ScanOptions { generator: Generator::Bits, ..options.clone() }.skipped_scales(), Some(3..=300));
+    assert_eq!(ScanOptions { scales: 3..=300, ..options.clone() }.skipped_scales(), Some(3..=300));
+    assert_eq!(ScanOptions { scales: -300..=1, ..options }.skipped_scales(), None);
+}
+
+#[test]
+fn exact_tie_iter() {
+    let values = ExactTieIter::new(10, false).collect::<Vec<_>>();
+    assert_eq!(values.len(), (1 << 10) - 1);
+    for (value, pr) in &values {
+        // exactly representable, with pr + 1 fractional digits ending with 5
+        let exact = BigDecimal::from_f64(f64::from_str(value).unwrap()).unwrap();
+        assert_eq!(exact, BigDecimal::from_str(value).unwrap(), "{value}");
+        assert_eq!(value.len(), pr + 3, "{value}");
+        assert!(value.ends_with('5'), "{value}");
+    }
+    let ints = IntegerParts::from_str("0..=3,10^6..=10^7").unwrap();
+    let total = ExactTieIter::total(8, &ints);
+    assert_eq!(total, 8 * 255);
+    let all = ExactTieIter::with_integers(8, true, ints.clone(), 24, 0..total).collect::<Vec<_>>();
+    let mut chunks = Vec::new();
+    for start in (0..total).step_by(100) {
+        chunks.extend(ExactTieIter::with_integers(8, true, ints.clone(), 24, start..start + 100));
+    }
+    assert_eq!(chunks, all);
+    for (value, _) in &all {
+        let exact = BigDecimal::from_f32(f32::from_str(value).unwrap()).unwrap();
+        assert_eq!(exact, BigDecimal::from_str(value).unwrap(), "{value}");
+    }
+    // 10^7 + 0.5 needs 25 bits, 10^6 - 1 + 2^-4 needs 24
+    assert!(!all.iter().any(|(value, _)| value.starts_with("-10000000.")));
+    assert!(all.iter().any(|(value, _)| value == "-999999.0625"));
+    assert!(!all.iter().any(|(value, _)| value == "-999999.03125"));
+    let options = ScanOptions { depth: 12, generator: Generator::Exact, integers: ints, ..ScanOptions::default() };
+    let summary = find_issues::<f64>(&options, None).unwrap();
+    assert_eq!(summary.tests, 8 * 4095);
+    assert_eq!(summary.errors, 0);
+    let summary = find_issues::<f64>(&ScanOptions { policy: Policy::AwayFromZero, ..options.clone() }, None).unwrap();
+    assert_eq!(summary.bugs, 0);
+    assert_eq!(summary.errors, summary.intents);
+    // the scaled values which aren't exact are skipped: all those of the negative scales but
+    // 0.625e-1 = 2^-4, and those of the positive ones which need too many bits
+    let options = ScanOptions { depth: 3, scales: -2..=0, integers: IntegerParts::default(), ..options };
+    let summary = find_issues::<f64>(&options, None).unwrap();
+    assert_eq!((summary.tests, summary.errors), (7 + 1, 0));
+    let summary = find_issues::<f32>(&ScanOptions { depth: 6, scales: 0..=12, ..options }, None).unwrap();
+    assert!(summary.tests < 63 * 13);
+    assert_eq!(summary.errors, 0);
+}
+
+#[test]
+fn find_issues_threads() {
+    let options = ScanOptions { depth: 4, threads: 1, ..ScanOptions::default() };
+    let reference = find_issues::<f64>(&options, None).unwrap();
+    assert_eq!(reference.tests, 1111);
+    assert_eq!(reference.bugs, 0);
+    assert_eq!(reference.errors, reference.intents);
+    for threads in [2, 3, 8] {
+        let options = ScanOptions { threads, ..options.clone() };
+        assert_eq!(find_issues::<f64>(&options, None).unwrap(), reference, "{threads} threads");
+    }
+    // the verbose output is in the order of the values, whatever the number of threads
+    let options = ScanOptions { scales: -2..=2, verbose: true, ..options };
+    for format in Format::ALL {
+        let mut single = Vec::new();
+        let summary = write_issues::<f64, _>(&mut single, &ScanOptions { format, ..options.clone() }, None, None).unwrap();
+        if format == Format::Text {
+            // the header, a line per value, and the summary
+            assert_eq!(String::from_utf8_lossy(&single).lines().count() as u64, 1 + summary.tests + 3);
+        }
+        let mut output = Vec::new();
+        write_issues::<f64, _>(&mut output, &ScanOptions { format, threads: 8, ..options.clone() }, None, None).unwrap();
+        assert!(output == single, "{} output with 8 threads", format.name());
+    }
+}
+
+#[test]
+fn find_issues_format() {
+    let options = ScanOptions { depth: 3, ..ScanOptions::default() };
+    let reference = find_issues::<f32>(&options, None).unwrap();
+    for format in Format::ALL {
+        let options = ScanOptions { format, ..options.clone() };
+        assert_eq!(find_issues::<f32>(&options, None).unwrap(), reference, "{}", format.name());
+    }
+    assert_eq!(Format::Csv.header().map(|h| h.split(',').count()), Some(13));
+    assert_eq!(Format::Json.header(), None);
+}
+
+#[test]
+fn find_issues_baseline() {
+    let options = ScanOptions { depth: 4, threads: 2, ..ScanOptions::default() };
+    let mut baseline = Baseline::default();
+    let summary = find_issues_into::<f64>(&options, None, Some(&mut baseline)).unwrap();
+    assert_eq!(baseline.entries.len() as u64, summary.bugs + summary.intents);
+    assert_eq!(baseline.entries[0].to_string(), "f64 0.05 1 0.1");
+    assert_eq!(Baseline::from_str(&baseline.to_string()), Ok(baseline.clone()));
+    let mut f32_baseline = Baseline::default();
+    find_issues_into::<f32>(&options, None, Some(&mut f32_baseline)).unwrap();
+    let (appeared, disappeared) = f32_baseline.diff(&baseline);
+    assert_eq!(appeared.len(), f32_baseline.entries.len());
+    assert_eq!(disappeared.len(), baseline.entries.len());
+    let (appeared, disappeared) = baseline.diff(&baseline);
+    assert!(appeared.is_empty() && disappeared.is_empty());
+}
+
+#[test]
+fn find_issues_sample() {
+    let mut rng = SplitMix64::new(1234);
+    for n in 0..100 {
+        assert_eq!(rng.next_u64(), SplitMix64::at(1234, n));
+    }
+    assert!((0..1000).all(|n| SplitMix64::at_below(1, n, 7) < 7));
+    let sample = Sample { count: 3000, seed: 99 };
+    let options = ScanOptions { depth: 12, sample: Some(sample), threads: 1, ..ScanOptions::default() };
+    let mut baseline = Baseline::default();
+    let reference = find_issues_into::<f64>(&options, None, Some(&mut baseline)).unwrap();
+    assert_eq!(reference.tests, 3000);
+    // a single value per draw
+    assert_eq!((reference.draws, reference.failed_draws), (3000, reference.errors));
+    let (low, high) = reference.error_rate_interval();
+    assert!(low < reference.error_rate() && reference.error_rate() < high);
+    // same values with any number of threads, and different ones with another seed
+    let mut other = Baseline::default();
+    let options = ScanOptions { threads: 4, ..options };
+    assert_eq!(find_issues_into::<f64>(&options, None, Some(&mut other)).unwrap(), reference);
+    assert_eq!(other, baseline);
+    let options = ScanOptions { sample: Some(Sample { seed: 100, ..sample }), ..options };
+    let mut other = Baseline::default();
+    find_issues_into::<f64>(&options, None, Some(&mut other)).unwrap();
+    assert_ne!(other, baseline);
+    // all the precisions of each bit pattern, except for the non-finite values
+    let options = ScanOptions { depth: 3, generator: Generator::Bits, ..options };
+    let summary = find_issues::<f32>(&options, None).unwrap();
+    assert!(summary.tests <= 4 * 3000 && summary.tests > 3 * 3000);
+    assert_eq!(summary.bugs, 0);
+    // the interval is on the draws, whose precisions aren't independent
+    assert!(summary.draws <= 3000 && summary.draws > 2900);
+    assert!(summary.failed_draws <= summary.draws && summary.failed_draws <= summary.errors);
+    let (low, high) = summary.error_rate_interval();
+    let rate = 100.0 * summary.failed_draws as f64 / summary.draws as f64;
+    assert!(low < rate && rate < high);
+}
+
+#[test]
+fn find_issues_checkpoint() {
+    let path = std::env::temp_dir().join(format!("rounding-test-{}.checkpoint", std::process::id()));
+    let options = ScanOptions {
+        depth: 6,
+        policy: Policy::HalfUp,
+        threads: 2,
+        checkpoint: Some(path.clone()),
+        checkpoint_interval: Duration::ZERO,
+        ..ScanOptions::default()
+    };
+    let reference = find_issues::<f32>(&options, None).unwrap();
+    let checkpoint = Checkpoint::load(&path).unwrap();
+    let total = RoundTestIter::total(6);
+    assert_eq!(checkpoint, Checkpoint {
+        float: "f32".to_string(),
+        depth: 6,
+        negative: false,
+        integers: IntegerParts::default(),
+        scales: 0..=0,
+        pattern: TiePattern::default(),
+        generator: Generator::Ties,
+        notation: Notation::Fixed,
+        policy: Policy::HalfUp,
+        sample: None,
+        next: total,
+        summary: reference
+    });
+    // the parameters of the checkpoint replace the options:
+    let other_options = ScanOptions { depth: 2, policy: Policy::Floor, checkpoint: None, ..options.clone() };
+    let start = Checkpoint { next: 0, summary: Summary::default(), ..checkpoint.clone() };
+    assert_eq!(find_issues::<f32>(&other_options, Some(&start)).unwrap(), reference);
+    let middle = Checkpoint { next: 100_000, summary: Summary::default(), ..checkpoint.clone() };
+    let rest = find_issues::<f32>(&options, Some(&middle)).unwrap();
+    assert_eq!(rest.tests, total - 100_000);
+    assert!(rest.errors < reference.errors);
+    assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint { next: total, summary: rest, ..checkpoint.clone() });
+    std::fs::remove_file(&path).unwrap();
+    // the invalid parameters are errors, instead of panics in the scan
+    let text = checkpoint.to_string();
+    for (valid, invalid) in [("depth=6", "depth=19"), ("depth=6", "depth=0"), ("float=f32", "float=f16")] {
+        assert!(Checkpoint::from_str(&text.replace(valid, invalid)).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_report() {
+    let suites = [
+        Suite { float: "f64".to_string(), depth: 3, summary: Summary { tests: 111, errors: 55, bugs: 0, intents: 55, ..Summary::default() } },
+        Suite { float: "f32".to_string(), depth: 3, summary: Summary { tests: 111, errors: 52, bugs: 2, intents: 50, ..Summary::default() } },
+    ];
+    let mut out = Vec::new();
+    write_report(&mut out, ReportFormat::Tap, &suites).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "TAP version 13\n1..4\n\
+        ok 1 - f64 Display bugs\n# 0 Display bugs / 111 f64 value(s) for depth 0-3\n\
+        ok 2 - f64 decimal-intent mismatches\n# 55 decimal-intent mismatches / 111 f64 value(s) for depth 0-3\n\
+        not ok 3 - f32 Display bugs\n# 2 Display bugs / 111 f32 value(s) for depth 0-3\n\
+        ok 4 - f32 decimal-intent mismatches\n# 50 decimal-intent mismatches / 111 f32 value(s) for depth 0-3\n");
+    let mut out = Vec::new();
+    write_report(&mut out, ReportFormat::JUnit, &suites).unwrap();
+    let xml = String::from_utf8(out).unwrap();
+    assert!(xml.contains(r#"<testsuites name="rounding" tests="4" failures="1">"#));
+    assert!(xml.contains(r#"<testcase classname="rounding.f32" name="Display bugs"><failure message="2 Display bugs / 111 f32 value(s) for depth 0-3"/></testcase>"#));
+    assert_eq!(xml.matches("<failure").count(), 1);
+}
+
+#[test]
+fn test_thresholds() {
+    let suites = [
+        Suite { float: "f64".to_string(), depth: 3, summary: Summary { tests: 111, errors: 55, bugs: 0, intents: 55, ..Summary::default() } },
+        Suite { float: "f32".to_string(), depth: 3, summary: Summary { tests: 111, errors: 52, bugs: 2, intents: 50, ..Summary::default() } },
+    ];
+    // the decimal-intent mismatches don't count
+    assert!(Thresholds { max_bugs: Some(2), max_rate: Some(1.81) }.exceeded(&suites).is_empty());
+    assert!(Thresholds::default().exceeded(&suites).is_empty());
+    assert_eq!(Thresholds { max_bugs: Some(0), max_rate: None }.exceeded(&suites),
+               ["f32: 2 Display bug(s), more than the maximum of 0"]);
+    assert_eq!(Thresholds { max_bugs: None, max_rate: Some(1.5) }.exceeded(&suites),
+               ["f32: 1.8 % of Display bugs, more than the maximum of 1.5 %"]);
+}
+
+#[test]
+fn test_verify_f32() {
+    let ranges = [
+        (0x0000_0000..0x0000_0800, 0x800),      // +0 and subnormals
+        (0x3dcc_c000..0x3dcc_d000, 0x1000),     // around 0.1
+        (0x7f7f_ff00..0x7f80_0100, 0x100),      // largest values, then +inf and NaN
+        (0xbf7f_f800..0xbf80_0800, 0x1000),     // around -1.0
+    ];
+    for (bits, exp_tested) in ranges {
+        let chunks = AtomicU64::new(0);
+        let (tested, mismatches) = verify_f32_range(bits.clone(), 4, 3, |_, total| {
+            assert_eq!(total, 1);
+            chunks.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(chunks.into_inner(), 1, "range {bits:x?}");
+        assert_eq!(tested, exp_tested, "range {bits:x?}");
+        assert_eq!(mismatches, vec![], "range {bits:x?}");
+    }
+}
+
 #[test]
 fn visual_test() {
     // not a real test, just a visual comparison
@@ -112,12 +885,46 @@ fn round_test_iter() {
         ("0.94", 1), ("0.95", 1), ("0.904", 2), ("0.905", 2), ("0.914", 2), ("0.915", 2), ("0.924", 2), ("0.925", 2),
         ("0.934", 2), ("0.935", 2), ("0.944", 2), ("0.945", 2), ("0.954", 2), ("0.955", 2), ("0.964", 2), ("0.965", 2),
         ("0.974", 2), ("0.975", 2), ("0.984", 2), ("0.985", 2), ("0.994", 2), ("0.995", 2)];
-    let exp_list = exp_list.iter().filter(|(a, _)| INIT_DIGIT == b'4' || a.ends_with('5')).collect::<Vec<_>>();
-    let iter = RoundTestIter::new(3, false);
-    for (idx, ((s, pr), (exp_s, exp_pr))) in iter.zip(exp_list).enumerate() {
-        let reference = format!("value index {idx}: ({s}, {pr}) instead of ({exp_s}, {exp_pr})");
-        assert_eq!(s, *exp_s, "{}", reference);
-        assert_eq!(pr, *exp_pr, "{}", reference);
+    for pattern in ["4,5", "5"] {
+        let exp_list = exp_list.iter().filter(|(a, _)| pattern == "4,5" || a.ends_with('5')).collect::<Vec<_>>();
+        let pattern = TiePattern::from_str(pattern).unwrap();
+        let iter = RoundTestIter::with_pattern(3, false, IntegerParts::default(), pattern, 0..u64::MAX);
+        assert_eq!(iter.len(), exp_list.len());
+        for (idx, ((s, pr), (exp_s, exp_pr))) in iter.zip(exp_list).enumerate() {
+            let reference = format!("value index {idx}: ({s}, {pr}) instead of ({exp_s}, {exp_pr})");
+            assert_eq!(s, *exp_s, "{}", reference);
+            assert_eq!(pr, *exp_pr, "{}", reference);
+        }
     }
     println!();
 }
+
+#[test]
+fn round_test_iter_pattern() {
+    let pattern = TiePattern::from_str("49{5},5,50{4}1").unwrap();
+    assert_eq!(pattern.endings(), ["499999", "5", "500001"]);
+    assert_eq!(TiePattern::from_str(&pattern.to_string()), Ok(pattern.clone()));
+    let ints = IntegerParts::from_str("0,7").unwrap();
+    let total = RoundTestIter::total_with(3, &ints, &pattern);
+    assert_eq!(total, 2 * 3 * 111);
+    let all = RoundTestIter::with_pattern(3, true, ints.clone(), pattern.clone(), 0..total).collect::<Vec<_>>();
+    assert_eq!(all.len() as u64, total);
+    assert_eq!(all[..4], [("-0.499999".to_string(), 0), ("-0.5".to_string(), 0), ("-0.500001".to_string(), 0),
+                          ("-0.0499999".to_string(), 1)]);
+    assert_eq!(all[333], ("-7.499999".to_string(), 0));
+    assert_eq!(all[665], ("-7.99500001".to_string(), 2));
+    // the values with the ending 5 are those of the default pattern
+    let ties = RoundTestIter::with_integers(3, true, ints.clone(), 0..u64::MAX).collect::<Vec<_>>();
+    assert_eq!(all.iter().skip(1).step_by(3).cloned().collect::<Vec<_>>(), ties);
+    for index in [0, 2, 3, 332, 333, 500, total - 1] {
+        let mut it = RoundTestIter::with_pattern(3, true, ints.clone(), pattern.clone(), index..total);
+        assert_eq!(it.next().as_ref(), Some(&all[index as usize]));
+        let mut it = RoundTestIter::with_pattern(3, true, ints.clone(), pattern.clone(), 0..total);
+        assert_eq!(it.nth(index as usize).as_ref(), Some(&all[index as usize]));
+    }
+    for spec in ["", "4,", "4{", "{3}", "4{x}", "a", "49{3", "4}"] {
+        assert!(TiePattern::from_str(spec).is_err(), "{spec}");
+    }
+    let options = ScanOptions { depth: 3, pattern, ..ScanOptions::default() };
+    assert_eq!(find_issues::<f64>(&options, None).unwrap().tests, 333);
+}